@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use crate::lexer::{Token::{self, *}, Operator::*};
+
+/* this module does conditional compilation, which happens on the tokens of a file before macros get expanded */
+/* #[cfg(...)] before a function or a statement removes it when its condition is false: */
+/*
+	#[cfg(libc)]
+	fn exit(code: i64) { ... }
+
+	#[cfg(not(libc))]
+	fn exit(code: i64) { ... }
+*/
+/* and cfg!(...) turns into 1 or 0, so that it can be used in a condition, like 'if (cfg!(debug)) { ... }' */
+/* a condition is a name, like libc, a name with a value, like target_arch = "x86_64", or not(...), all(...) and any(...) of other conditions */
+/* names get defined with -D NAME or -D NAME=VALUE, and the compiler defines some of them itself (see options.rs) */
+
+/* name -> value, names that are defined without a value have None */
+pub type Cfg = HashMap<String, Option<String>>;
+
+/* returns the index of the ) that closes the ( at tokens[open], it has to be on the same line */
+fn closing_paren(tokens: &[Token], open: usize, line: i64) -> Result<usize, (String, i64)> {
+	let mut depth = 0;
+
+	for (i, v) in tokens.iter().enumerate().skip(open) {
+		match v {
+			Operator(LeftParen) => depth += 1,
+			Operator(RightParen) => depth -= 1,
+			Newline => break,
+			_ => ()
+		}
+
+		if (depth == 0) {
+			return Ok(i);
+		}
+	}
+
+	Err(("expected ')' to close the condition of cfg".to_owned(), line))
+}
+
+/* splits the arguments of not(...), all(...) and any(...) at the commas that aren't inside of parentheses */
+fn split_conditions(tokens: &[Token]) -> Vec<&[Token]> {
+	let mut output: Vec<&[Token]> = Vec::new();
+	let mut depth = 0;
+	let mut start = 0;
+
+	for (i, v) in tokens.iter().enumerate() {
+		match v {
+			Operator(LeftParen) => depth += 1,
+			Operator(RightParen) => depth -= 1,
+			Operator(Comma) if depth == 0 => {
+				output.push(&tokens[start..i]);
+				start = i + 1;
+			}
+			_ => ()
+		}
+	}
+
+	if (start < tokens.len()) {
+		output.push(&tokens[start..]);
+	}
+
+	output
+}
+
+fn eval_condition(cfg: &Cfg, tokens: &[Token], line: i64) -> Result<bool, (String, i64)> {
+	match tokens {
+		[Identifier(name)] => Ok(cfg.contains_key(name)),
+
+		/* values can be written as string literals, numbers or identifiers, they all get compared as text */
+		[Identifier(name), Operator(Equal), StringLiteral(value) | Numerical(value) | Identifier(value)] => {
+			Ok(cfg.get(name).is_some_and(|x| x.as_ref() == Some(value)))
+		}
+
+		[Identifier(function), Operator(LeftParen), inner @ .., Operator(RightParen)] if closing_paren(tokens, 1, line)? == tokens.len() - 1 => {
+			let conditions = split_conditions(inner);
+
+			match function.as_str() {
+				"not" if conditions.len() == 1 => Ok(!eval_condition(cfg, conditions[0], line)?),
+				"not" => Err((format!("not(...) in cfg takes 1 condition, not {}", conditions.len()), line)),
+				"all" => {
+					for i in conditions {
+						if (!eval_condition(cfg, i, line)?) {
+							return Ok(false);
+						}
+					}
+					Ok(true)
+				}
+				"any" => {
+					for i in conditions {
+						if (eval_condition(cfg, i, line)?) {
+							return Ok(true);
+						}
+					}
+					Ok(false)
+				}
+				_ => Err((format!("unknown cfg function '{function}', expected not, all or any"), line))
+			}
+		}
+
+		[] => Err(("expected a condition in cfg, like cfg(libc) or cfg(not(debug))".to_owned(), line)),
+		_ => {
+			let condition: Vec<String> = tokens.iter().map(|x| x.to_string()).collect();
+			Err((format!("invalid cfg condition [{}], expected a name, a name = \"value\", or not(...), all(...) or any(...)", condition.join(", ")), line))
+		}
+	}
+}
+
+/* skips the function or statement that starts at tokens[start] and returns the index right after it */
+/* the newlines in it get pushed to 'output', so that the line numbers after it stay correct */
+fn skip_item(tokens: &[Token], start: usize, output: &mut Vec<Token>, line: &mut i64) -> Result<usize, (String, i64)> {
+	let mut i = start;
+
+	/* the item can be on the lines after the attribute, and can have more attributes before it */
+	loop {
+		match tokens.get(i) {
+			Some(Newline) => {
+				*line += 1;
+				output.push(Newline);
+				i += 1;
+			}
+			Some(Operator(Hash)) => {
+				match tokens[i..].iter().position(|x| matches!(x, Operator(RightSquare) | Newline)) {
+					Some(x) if matches!(tokens[i + x], Operator(RightSquare)) => i += x + 1,
+					_ => return Err(("expected ']' to close the attribute after #[cfg(...)]".to_owned(), *line))
+				}
+			}
+			Some(Operator(RightCurly)) | None => return Err(("expected a function or a statement after #[cfg(...)]".to_owned(), *line)),
+			Some(_) => break
+		}
+	}
+
+	/* it ends at the end of the line, unless there's a block in it that goes on for more lines */
+	let mut depth = 0;
+	while let Some(x) = tokens.get(i) {
+		match x {
+			Operator(LeftParen) | Operator(LeftSquare) | Operator(LeftCurly) => depth += 1,
+			Operator(RightParen) | Operator(RightSquare) | Operator(RightCurly) => {
+				/* the } of the block that the statement is in, like in '{ #[cfg(libc)] f() }' */
+				if (depth == 0) {
+					break;
+				}
+				depth -= 1;
+			}
+			Newline if depth == 0 => break,
+			Newline => {
+				*line += 1;
+				output.push(Newline);
+			}
+			_ => ()
+		}
+
+		i += 1;
+	}
+
+	Ok(i)
+}
+
+/* removes everything that has a #[cfg(...)] with a false condition, and replaces every cfg!(...) with 1 or 0 */
+pub fn apply(input: Vec<Token>, cfg: &Cfg) -> Result<Vec<Token>, (String, i64)> {
+	let mut output: Vec<Token> = Vec::new();
+	let mut line = 1;
+	let mut i = 0;
+
+	while (i < input.len()) {
+		match &input[i..] {
+			[Operator(Hash), Operator(LeftSquare), Identifier(name), Operator(LeftParen), ..] if name == "cfg" => {
+				let end = closing_paren(&input, i + 3, line)?;
+				if (!matches!(input.get(end + 1), Some(Operator(RightSquare)))) {
+					return Err(("expected ']' after the condition of cfg, cfg has to be in its own #[...], like #[cfg(libc)]".to_owned(), line));
+				}
+
+				let enabled = eval_condition(cfg, &input[i+4..end], line)?;
+				i = end + 2;
+
+				if (!enabled) {
+					i = skip_item(&input, i, &mut output, &mut line)?;
+				}
+				continue;
+			}
+			[Identifier(name), ..] if name == "cfg!" => {
+				if (!matches!(input.get(i + 1), Some(Operator(LeftParen)))) {
+					return Err(("expected '(' after cfg!, like cfg!(libc)".to_owned(), line));
+				}
+
+				let end = closing_paren(&input, i + 1, line)?;
+				let enabled = eval_condition(cfg, &input[i+2..end], line)?;
+
+				output.push(Numerical(if (enabled) {
+					"1"
+				}
+				else {
+					"0"
+				}.to_owned()));
+
+				i = end + 1;
+				continue;
+			}
+			[Newline, ..] => line += 1,
+			_ => ()
+		}
+
+		output.push(input[i].clone());
+		i += 1;
+	}
+
+	Ok(output)
+}
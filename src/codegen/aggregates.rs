@@ -0,0 +1,333 @@
+use super::*;
+
+/* tuples don't fit in a single register, so unlike the values that eval_expression deals with, they always live in memory */
+/* the stack slots that hold them are always padded to a multiple of 8 bytes, so they can be safely moved around 8 bytes at a time */
+
+/* given the address of a tuple like [rbp-24] and the offset of one of its elements, returns the address of that element, like [rbp-24+8] */
+pub fn member_address(addr: &str, offset: i32) -> String {
+	if (offset == 0) {
+		return addr.to_owned();
+	}
+
+	format!("{}+{offset}]", &addr[..addr.len()-1])
+}
+
+/* copies a tuple from one place in memory to another, in chunks that are as big as possible */
+pub fn copy_tuple(state: &mut State, from: &str, to: &str, datatype: &DataType) {
+	let mut offset = 0;
+
+	while (offset < datatype.byte_size) {
+		let word = match (datatype.byte_size - offset) {
+			8.. => WordType::QuadWord,
+			4..=7 => WordType::DoubleWord,
+			2..=3 => WordType::Word,
+			_ => WordType::Byte
+		};
+		let accumulator = get_accumulator(&word);
+
+		state.textsect.push_str(&format!("\tmov {accumulator}, {word} {}\n", member_address(from, offset)));
+		state.textsect.push_str(&format!("\tmov {word} {}, {accumulator}\n", member_address(to, offset)));
+
+		offset += match word {
+			WordType::QuadWord => 8,
+			WordType::DoubleWord => 4,
+			WordType::Word => 2,
+			WordType::Byte => 1
+		};
+	}
+}
+
+/* evaluates an expression and stores the result at 'addr' */
+/* this is what tuple expressions use to write each of their elements into memory */
+pub fn store_expression(state: &mut State, expr: &Expression, datatype: &DataType, addr: &str) -> Result<(), (String, i64)> {
+	if (datatype.is_tuple()) {
+		let value = eval_tuple(state, expr, datatype)?;
+		copy_tuple(state, &value, addr, datatype);
+
+		return Ok(());
+	}
+
+	let mut value = eval_expression(state, expr, datatype)?;
+
+	/* we can't mov a memory location to another memory location */
+	if (value.starts_with('[')) {
+		let accumulator = get_accumulator(&datatype.word);
+		state.textsect.push_str(&format!("\tmov {accumulator}, {value}\n"));
+
+		value = accumulator.to_owned();
+	}
+
+	state.textsect.push_str(&format!("\tmov {} {addr}, {value}\n", datatype.word));
+	Ok(())
+}
+
+/* how a value gets passed to or returned from a function, as per the system v abi */
+/* catlang doesn't have any floating point types, so nothing ever gets classified as SSE */
+#[derive(PartialEq)]
+pub enum PassingClass {
+	/* passed in this many general purpose registers (one for every 8 bytes) */
+	Integer(usize),
+	/* passed on the stack, or when returning, written to memory that the caller passes a hidden pointer to in rdi */
+	Memory
+}
+
+pub fn classify(datatype: &DataType) -> PassingClass {
+	if (datatype.byte_size > 16) {
+		PassingClass::Memory
+	}
+	else {
+		PassingClass::Integer((datatype.byte_size as usize).div_ceil(8))
+	}
+}
+
+/* figures out which argument registers every argument of a function goes into, None meaning that it gets passed on the stack */
+/* arguments are given registers from left to right, and if a tuple doesn't fit in the registers that are left it goes on the stack in its entirety */
+/* the scalar arguments after it can still use those registers though */
+pub fn argument_locations(arg_types: &[DataType], hidden_return_pointer: bool) -> Vec<Option<usize>> {
+	let mut locations = Vec::new();
+
+	/* the hidden return pointer takes up rdi */
+	let mut next_register = if (hidden_return_pointer) { 1 } else { 0 };
+
+	for i in arg_types {
+		match classify(i) {
+			PassingClass::Integer(registers) if next_register + registers <= 6 => {
+				locations.push(Some(next_register));
+				next_register += registers;
+			}
+			_ => locations.push(None)
+		}
+	}
+
+	locations
+}
+
+/* tuples that are 16 bytes or smaller get returned in rax and rdx, as per the system v abi */
+/* the first 8 bytes go into rax, the next 8 go into rdx */
+pub fn load_return_registers(state: &mut State, addr: &str, datatype: &DataType) {
+	state.textsect.push_str(&format!("\tmov rax, qword {addr}\n"));
+
+	if (datatype.byte_size > 8) {
+		state.textsect.push_str(&format!("\tmov rdx, qword {}\n", member_address(addr, 8)));
+	}
+}
+
+/* the opposite of load_return_registers, this is used by the caller to put the returned tuple back into memory */
+pub fn store_return_registers(state: &mut State, addr: &str, datatype: &DataType) {
+	state.textsect.push_str(&format!("\tmov qword {addr}, rax\n"));
+
+	if (datatype.byte_size > 8) {
+		state.textsect.push_str(&format!("\tmov qword {}, rdx\n", member_address(addr, 8)));
+	}
+}
+
+/* evaluates s[a..b] into a new slice that points into s, and returns its address, the iterator should be right after the [ */
+/* a and b both have to be between 0 and the length of s, and a can't be bigger than b, otherwise the program panics */
+fn sub_slice(state: &mut State, name: &str, iter: &mut core::slice::Iter<Token>, expected_type: &DataType) -> Result<String, (String, i64)> {
+	let var = match state.function.local_variables.get(name) {
+		Some(x) => x.clone(),
+		None => return Err((format!("variable '{name}' is not defined in the current scope"), state.line))
+	};
+
+	let element_type = match var.vartype.element_type(state)? {
+		Some(x) => x,
+		None => return Err((format!("only strings and slices can be sliced, but '{name}' is of type '{}'", var.vartype.string), state.line))
+	};
+
+	if (&var.vartype != expected_type) {
+		return Err((format!("expected expression to evaluate to type '{}', but slicing '{name}' gives a '{}'", expected_type.string, var.vartype.string), state.line));
+	}
+
+	/* the start and the end of the range, until the matching ] */
+	let mut start: Expression = Vec::new();
+	let mut end: Option<Expression> = None;
+	let mut depth = 0;
+
+	loop {
+		let token = match iter.next() {
+			Some(x) => x,
+			None => return Err((format!("expected ']' after the range in '{name}[...]'"), state.line))
+		};
+
+		match token {
+			Operator(RightSquare) if depth == 0 => break,
+			Operator(DotDot) if depth == 0 && end.is_none() => {
+				end = Some(Vec::new());
+				continue;
+			}
+			Operator(LeftSquare) => depth += 1,
+			Operator(RightSquare) => depth -= 1,
+			_ => ()
+		}
+
+		match &mut end {
+			Some(x) => x.push(token.clone()),
+			None => start.push(token.clone())
+		}
+	}
+
+	let end = match end {
+		Some(x) => x,
+		None => return Err((format!("expected a range like {name}[a..b] to slice '{name}', indexing isn't supported"), state.line))
+	};
+
+	let i64 = DataType::new("i64", state.line)?;
+	let length = member_address(&var.addr, 8);
+
+	/* a missing start is 0, and a missing end is the length */
+	let start_addr = reserve_stack(state, &i64);
+	if (start.is_empty()) {
+		state.textsect.push_str(&format!("\tmov qword {start_addr}, 0\n"));
+	}
+	else {
+		store_expression(state, &start, &i64, &start_addr)?;
+	}
+
+	let end_addr = reserve_stack(state, &i64);
+	if (end.is_empty()) {
+		state.textsect.push_str(&format!("\tmov rax, qword {length}\n"));
+		state.textsect.push_str(&format!("\tmov qword {end_addr}, rax\n"));
+	}
+	else {
+		store_expression(state, &end, &i64, &end_addr)?;
+	}
+
+	/* the comparisons are unsigned, so a negative start or end counts as too big */
+	state.textsect.push_str(&format!("\tmov rax, qword {start_addr}\n"));
+	state.textsect.push_str(&format!("\tmov rcx, qword {end_addr}\n"));
+
+	state.labels += 2;
+	let (out_of_bounds, in_bounds) = (format!(".L{}", state.labels-1), format!(".L{}", state.labels));
+
+	state.textsect.push_str("\tcmp rax, rcx\n");
+	state.textsect.push_str(&format!("\tja {out_of_bounds}\n"));
+	state.textsect.push_str(&format!("\tcmp rcx, qword {length}\n"));
+	state.textsect.push_str(&format!("\tjbe {in_bounds}\n"));
+	state.textsect.push_str(&format!("{out_of_bounds}:\n"));
+	macros::emit_panic(state, &format!("range out of bounds when slicing '{name}'"));
+	state.textsect.push_str(&format!("{in_bounds}:\n"));
+
+	/* the new slice starts at ptr + start, and is end - start long */
+	let addr = reserve_stack(state, expected_type);
+	state.textsect.push_str("\tsub rcx, rax\n");
+	state.textsect.push_str(&format!("\tmov qword {}, rcx\n", member_address(&addr, 8)));
+	state.textsect.push_str(&format!("\timul rax, rax, {}\n", element_type.byte_size));
+	state.textsect.push_str(&format!("\tadd rax, qword {}\n", var.addr));
+	state.textsect.push_str(&format!("\tmov qword {addr}, rax\n"));
+
+	Ok(addr)
+}
+
+/* evaluates an expression that results in a tuple and returns the address of where that tuple is stored at */
+/* an input of [(, 5, ",", x, )] with expected_type as (i32, i64) will write 5 and the value of x into a new stack slot and return its address */
+/* an input of [t] where t is a tuple variable will just return the address of t, like [rbp-24] */
+pub fn eval_tuple(state: &mut State, expr: &Expression, expected_type: &DataType) -> Result<String, (String, i64)> {
+	let expr = &lower_method_calls(state, expr)?;
+	let expr = &lower_try(state, expr)?;
+	let expr = &lower_if_expressions(state, expr, expected_type)?;
+	let expr = &lower_operators(state, expr, expected_type)?;
+	let mut iter = expr.iter();
+
+	/* anything that implements the trait can be used where a dyn is expected */
+	if (expected_type.string.starts_with("dyn ") && &infer_type(state, expr)? != expected_type) {
+		return coerce_to_dyn(state, expr, expected_type);
+	}
+
+	let addr = match (iter.next(), iter.clone().next()) {
+		/* tuple expressions, like (5, x) */
+		(Some(Operator(LeftParen)), _) => {
+			let elements = process_function_parameters(&mut iter);
+
+			if (elements.len() != expected_type.members.len()) {
+				return Err((format!("expected a tuple of type '{}', but the tuple expression has {} elements", expected_type.string, elements.len()), state.line));
+			}
+
+			let addr = reserve_stack(state, expected_type);
+			for (i, offset) in expected_type.member_offsets().into_iter().enumerate() {
+				store_expression(state, &elements[i], &expected_type.members[i], &member_address(&addr, offset))?;
+			}
+
+			addr
+		}
+
+		/* results, like Ok(5) or Err(x) */
+		(Some(Identifier(name)), Some(Operator(LeftParen))) if (name == "Ok" || name == "Err") && !state.functions.contains_key(name) => {
+			iter.next(); /* strip ( */
+			let args = process_function_parameters(&mut iter);
+
+			construct_result(state, name, &args, expected_type)?
+		}
+
+		/* function calls */
+		(Some(Identifier(name)), Some(Operator(LeftParen))) if !name.ends_with('!') => {
+			iter.next(); /* strip ( */
+			let args = process_function_parameters(&mut iter);
+
+			let (return_type, return_buffer) = call_function(state, name, &args)?;
+
+			match &return_type {
+				Some(x) if x == expected_type => (),
+				Some(x) => return Err((format!("expected expression to evaluate to '{}', but the return type of '{name}' is '{}'", expected_type.string, x.string), state.line)),
+				None => return Err((format!("attempted to get return value of function '{name}', but it does not return anything"), state.line))
+			}
+
+			/* big tuples have already been written to memory by the function we called */
+			match return_buffer {
+				Some(x) => x,
+				None => {
+					let addr = reserve_stack(state, expected_type);
+					store_return_registers(state, &addr, expected_type);
+
+					addr
+				}
+			}
+		}
+
+		/* macro calls, like include_bytes! which returns a pointer and a length */
+		(Some(Identifier(name)), Some(Operator(LeftParen))) => {
+			iter.next(); /* strip ( */
+			let args = process_function_parameters(&mut iter);
+
+			match macros::macro_return_type(state, name, &args)? {
+				Some(x) if &x == expected_type => (),
+				Some(x) => return Err((format!("expected expression to evaluate to '{}', but macro '{name}' returns '{}'", expected_type.string, x.string), state.line)),
+				None => return Err((format!("attempted to get return value of macro '{name}', but it does not return anything"), state.line))
+			}
+
+			/* this only panics if we set up the macro wrong */
+			macros::call_macro(state, name, &args)?.unwrap_or_else(|| panic!("macro {name} returns a tuple, but when calling it, it did not return one"))
+		}
+
+		/* string literals, which become a str that points to .rodata */
+		(Some(StringLiteral(x)), _) if expected_type.string == "str" => resolve_str_literal(state, x)?,
+
+		/* sub-slices, like s[1..3], s[..n] or s[2..] */
+		(Some(Identifier(x)), Some(Operator(LeftSquare))) => {
+			iter.next(); /* strip [ */
+			sub_slice(state, x, &mut iter, expected_type)?
+		}
+
+		/* variables */
+		(Some(Identifier(x)), _) => {
+			let var = match state.function.local_variables.get(x) {
+				Some(x) => x,
+				None => return Err((format!("variable '{x}' is not defined in the current scope"), state.line))
+			};
+
+			if (&var.vartype != expected_type) {
+				return Err((format!("expected expression to evaluate to type '{}', but the type of '{x}' is '{}'", expected_type.string, var.vartype.string), state.line));
+			}
+
+			var.addr.clone()
+		}
+
+		(Some(x), _) => return Err((format!("expected a tuple expression, function call or variable of type '{}', but got {x}", expected_type.string), state.line)),
+		(None, _) => return Err((format!("expected a tuple expression, function call or variable of type '{}', but got nothing", expected_type.string), state.line))
+	};
+
+	if let Some(x) = iter.next() {
+		return Err((format!("unexpected {x} after tuple of type '{}', tuples cannot be used in arithmetic expressions", expected_type.string), state.line));
+	}
+
+	Ok(addr)
+}
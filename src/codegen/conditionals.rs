@@ -0,0 +1,212 @@
+use super::*;
+use crate::lexer::Keyword;
+use crate::parser::comparison_operator;
+
+/* if expressions pick one of two values, like this: */
+/*
+	let sign = if (x < 0) { -1 } else { 1 }
+*/
+/* both branches have to be there and have to be of the same type, and 'else if' can be used to pick from more than two */
+/* when both branches are just arithmetic on variables and literals, both get evaluated and cmov picks one, so there's no jump */
+/* otherwise only the branch that the condition picks gets evaluated, like with an if statement */
+
+struct IfExpression {
+	/* the condition, which is 'lhs operator rhs', or 'lhs != 0' if there's no comparison in it */
+	lhs: Expression,
+	operator: ComparisonOperator,
+	rhs: Expression,
+
+	then: Expression,
+	otherwise: Expression
+}
+
+/* returns the index of the token that closes the bracket at tokens[0], like the ) that goes with a ( */
+fn matching_bracket(tokens: &[Token]) -> Option<usize> {
+	let mut depth = 0;
+
+	for (i, v) in tokens.iter().enumerate() {
+		match v {
+			Operator(LeftParen) | Operator(LeftSquare) | Operator(LeftCurly) => depth += 1,
+			Operator(RightParen) | Operator(RightSquare) | Operator(RightCurly) => depth -= 1,
+			_ => ()
+		}
+
+		if (depth == 0) {
+			return Some(i);
+		}
+	}
+
+	None
+}
+
+/* parses the if expression at the start of 'tokens', and returns it with the amount of tokens that it takes up */
+fn parse_if_expression(state: &State, tokens: &[Token]) -> Result<(IfExpression, usize), (String, i64)> {
+	/* tokens[0] is the if keyword */
+	let condition_end = match tokens.get(1) {
+		Some(Operator(LeftParen)) => match matching_bracket(&tokens[1..]) {
+			Some(x) => x + 1,
+			None => return Err(("expected ')' after the condition of if expression".to_owned(), state.line))
+		},
+		_ => return Err(("expected '(' after if keyword".to_owned(), state.line))
+	};
+
+	let condition = &tokens[2..condition_end];
+	let (lhs, operator, rhs) = match condition.iter().position(|x| comparison_operator(x).is_some()) {
+		/* unwrap will never fail, we just found the operator */
+		Some(i) => (condition[..i].to_vec(), comparison_operator(&condition[i]).unwrap(), condition[i+1..].to_vec()),
+		None => (condition.to_vec(), ComparisonOperator::NotEqual, vec![Numerical("0".to_owned())])
+	};
+
+	let (then, then_end) = branch(state, tokens, condition_end + 1)?;
+
+	if (!matches!(tokens.get(then_end + 1), Some(Keyword(Keyword::Else)))) {
+		return Err(("expected 'else' after the first branch of if expression, an if expression needs a value for when the condition is false".to_owned(), state.line));
+	}
+
+	/* 'else if' is another if expression in the else branch */
+	let (otherwise, end) = if let Some(Keyword(Keyword::If)) = tokens.get(then_end + 2) {
+		let (_, length) = parse_if_expression(state, &tokens[then_end + 2..])?;
+		(tokens[then_end + 2..then_end + 2 + length].to_vec(), then_end + 1 + length)
+	}
+	else {
+		branch(state, tokens, then_end + 2)?
+	};
+
+	Ok((IfExpression { lhs, operator, rhs, then, otherwise }, end + 1))
+}
+
+/* returns the expression inside of the curly braces at tokens[start], and the index of the closing curly brace */
+fn branch(state: &State, tokens: &[Token], start: usize) -> Result<(Expression, usize), (String, i64)> {
+	let end = match tokens.get(start) {
+		Some(Operator(LeftCurly)) => match matching_bracket(&tokens[start..]) {
+			Some(x) => start + x,
+			None => return Err(("expected '}' to close the branch of if expression, if expressions have to be on one line".to_owned(), state.line))
+		},
+		_ => return Err(("expected '{' before the value of the branch of if expression".to_owned(), state.line))
+	};
+
+	if (end == start + 1) {
+		return Err(("the branches of an if expression can't be empty, they have to have a value".to_owned(), state.line));
+	}
+
+	Ok((tokens[start+1..end].to_vec(), end))
+}
+
+/* branches that are only arithmetic on literals and variables can be evaluated even if they don't get picked */
+/* function calls could do anything, and division could divide by zero */
+fn is_side_effect_free(state: &State, expr: &Expression, datatype: &DataType) -> Result<bool, (String, i64)> {
+	/* an overloaded operator is a call to the method that implements it (see operators.rs), so it gets checked after they've turned into calls */
+	let expr = lower_operators(state, expr, datatype)?;
+
+	Ok(expr.iter().enumerate().all(|(i, v)| match v {
+		Numerical(_) => true,
+		Identifier(x) => !x.ends_with('!') && !matches!(expr.get(i+1), Some(Operator(LeftParen)) | Some(Operator(LeftSquare))),
+		Operator(Plus) | Operator(Dash) | Operator(Star) | Operator(Dot) => true,
+		_ => false
+	}))
+}
+
+/* int literals, like 5 or -1 */
+fn is_literal(expr: &Expression) -> bool {
+	matches!(expr.as_slice(), [Numerical(_)] | [Operator(Dash), Numerical(_)])
+}
+
+/* evaluates an if expression into a new stack slot and returns its address */
+fn eval_if_expression(state: &mut State, expr: &IfExpression, datatype: &DataType) -> Result<String, (String, i64)> {
+	let addr = reserve_stack(state, datatype);
+
+	/* cmov can't move bytes */
+	if (!datatype.is_tuple() && datatype.word != WordType::Byte && is_side_effect_free(state, &expr.then, datatype)? && is_side_effect_free(state, &expr.otherwise, datatype)?) {
+		let then = reserve_stack(state, datatype);
+		store_expression(state, &expr.otherwise, datatype, &addr)?;
+		store_expression(state, &expr.then, datatype, &then)?;
+
+		/* the condition goes last, evaluating the branches would overwrite the flags */
+		compare(state, &expr.lhs, &expr.rhs)?;
+
+		let accumulator = get_accumulator(&datatype.word);
+		let condition = &jump_instruction(&expr.operator, true)[1..];
+
+		state.textsect.push_str(&format!("\tmov {accumulator}, {} {addr}\n", datatype.word));
+		state.textsect.push_str(&format!("\tcmov{condition} {accumulator}, {} {then}\n", datatype.word));
+		state.textsect.push_str(&format!("\tmov {} {addr}, {accumulator}\n", datatype.word));
+
+		return Ok(addr);
+	}
+
+	state.labels += 2;
+	let (else_label, end_label) = (format!(".L{}", state.labels-1), format!(".L{}", state.labels));
+
+	compare(state, &expr.lhs, &expr.rhs)?;
+	state.textsect.push_str(&format!("\t{} {else_label}\n", jump_instruction(&expr.operator, false)));
+
+	store_expression(state, &expr.then, datatype, &addr)?;
+	state.textsect.push_str(&format!("\tjmp {end_label}\n"));
+
+	state.textsect.push_str(&format!("{else_label}:\n"));
+	store_expression(state, &expr.otherwise, datatype, &addr)?;
+	state.textsect.push_str(&format!("{end_label}:\n"));
+
+	Ok(addr)
+}
+
+/* evaluates every if expression in an expression of type 'datatype' and replaces it with a hidden variable that holds its value */
+/* so 'x + if (a < b) { a } else { b }' becomes something like 'x + ?4' */
+/* if expressions inside of parentheses get dealt with when whatever is in them gets evaluated */
+pub fn lower_if_expressions(state: &mut State, expr: &Expression, datatype: &DataType) -> Result<Expression, (String, i64)> {
+	if (!expr.iter().any(|x| matches!(x, Keyword(Keyword::If)))) {
+		return Ok(expr.clone());
+	}
+
+	let mut output: Expression = Vec::new();
+	let mut depth = 0;
+	let mut i = 0;
+
+	while (i < expr.len()) {
+		match &expr[i] {
+			Operator(LeftParen) | Operator(LeftSquare) => depth += 1,
+			Operator(RightParen) | Operator(RightSquare) => depth -= 1,
+			Keyword(Keyword::If) if depth == 0 => {
+				let (if_expression, length) = parse_if_expression(state, &expr[i..])?;
+				let addr = eval_if_expression(state, &if_expression, datatype)?;
+
+				/* the name can't be typed in a source file, so it can never clash with a real variable */
+				state.labels += 1;
+				let name = format!("?{}", state.labels);
+				state.function.local_variables.insert(name.clone(), Variable { addr, vartype: datatype.clone() });
+
+				output.push(Identifier(name));
+				i += length;
+				continue;
+			}
+			_ => ()
+		}
+
+		output.push(expr[i].clone());
+		i += 1;
+	}
+
+	Ok(output)
+}
+
+/* returns the type of an if expression at the start of an expression */
+/* int literals don't decide the type if the other branch isn't one, so 'if (c) { -1 } else { x }' has the type of x */
+pub fn infer_if_type(state: &mut State, expr: &Expression) -> Result<Option<DataType>, (String, i64)> {
+	if (!matches!(expr.first(), Some(Keyword(Keyword::If)))) {
+		return Ok(None);
+	}
+
+	let (if_expression, _) = parse_if_expression(state, expr)?;
+	let then = infer_type(state, &if_expression.then)?;
+	let otherwise = infer_type(state, &if_expression.otherwise)?;
+
+	if (then == otherwise || is_literal(&if_expression.then)) {
+		return Ok(Some(otherwise));
+	}
+
+	if (is_literal(&if_expression.otherwise)) {
+		return Ok(Some(then));
+	}
+
+	Err((format!("the branches of an if expression have to be of the same type, but the first one is '{}' and the second one is '{}'", then.string, otherwise.string), state.line))
+}
@@ -5,6 +5,10 @@ use super::*;
 /* another example input of ["hello world"] would return the identifier for this string literal, so something like L0 or L1 */
 /* another example input of [5] would return 5 */
 pub fn eval_expression(state: &mut State, expr: &Expression, expected_type: &DataType) -> Result<String, (String, i64)> {
+	let expr = &lower_method_calls(state, expr)?;
+	let expr = &lower_try(state, expr)?;
+	let expr = &lower_if_expressions(state, expr, expected_type)?;
+	let expr = &lower_operators(state, expr, expected_type)?;
 	let mut iter = expr.iter();
 
 	/* what this function does is it evaluates a single element of an expression, a sort of "miniexpression" */
@@ -15,6 +19,25 @@ pub fn eval_expression(state: &mut State, expr: &Expression, expected_type: &Dat
 	fn eval_miniexpression(state: &mut State, iter: &mut core::slice::Iter<Token>, expected_type: &DataType) -> Result<String, (String, i64)> {
 		Ok(match (iter.next(), iter.clone().peekable().peek()) {
 			(Some(Numerical(x)), _) => x.to_string(),
+
+			/* unary minus, like -1 or -x */
+			(Some(Operator(Dash)), _) => {
+				let value = eval_miniexpression(state, iter, expected_type)?;
+				let is_number = |x: &str| x.starts_with(|c: char| c.is_ascii_digit());
+
+				/* literals just become negative literals */
+				match value.strip_prefix('-') {
+					Some(x) if is_number(x) => x.to_owned(),
+					_ if is_number(&value) => format!("-{value}"),
+					_ => {
+						let r11 = get_r11(&expected_type.word);
+						state.textsect.push_str(&format!("\tmov {r11}, {value}\n"));
+						state.textsect.push_str(&format!("\tneg {r11}\n"));
+
+						r11.to_owned()
+					}
+				}
+			}
 			(Some(StringLiteral(x)), _) => resolve_string_literal(&mut state.datasect, x),
 
 			/* function calls */
@@ -22,12 +45,7 @@ pub fn eval_expression(state: &mut State, expr: &Expression, expected_type: &Dat
 				iter.next(); /* strip ( */
 				let args = process_function_parameters(iter);
 				
-				call_function(state, name, &args)?;
-
-				/* unwrap will never fail as call_funtion will have hanndled it at this point */
-				let function = state.functions.get(name).unwrap();
-
-				let return_type = match &function.return_type {
+				let return_type = match call_function(state, name, &args)?.0 {
 					Some(x) => x.clone(),
 					None => return Err((format!("attempted to get return value of function '{name}', but it does not return anything"), state.line))
 				};
@@ -44,16 +62,40 @@ pub fn eval_expression(state: &mut State, expr: &Expression, expected_type: &Dat
 				iter.next(); /* strip ( */
 				let args = process_function_parameters(iter);
 				
-				let macro_obj = macros::get_macro(state, name)?;
-
-				match macro_obj.return_type {
-					Some(x) => DataType::new(x, state.line)?,
+				let return_type = match macros::macro_return_type(state, name, &args)? {
+					Some(x) => x,
 					None => return Err((format!("attempted to get return value of macro '{name}', but it does not return anything"), state.line))
 				};
 
+				if (return_type.is_tuple()) {
+					return Err((format!("macro '{name}' returns the tuple '{}', which cannot be used in an expression, destructure it with 'let (a, b) = {name}(...)' instead", return_type.string), state.line));
+				}
+
+				/* dbg! evaluates to its argument, so its type has to match */
+				if (name == "dbg!" && &return_type != expected_type) {
+					return Err((format!("expected expression to evaluate to '{}', but the argument of dbg! is of type '{}'", expected_type.string, return_type.string), state.line));
+				}
+
+				/* and unwrap! and unwrap_err! evaluate to what's inside of their argument */
+				if ((name == "unwrap!" || name == "unwrap_err!") && &return_type != expected_type) {
+					return Err((format!("expected expression to evaluate to '{}', but {name} evaluates to a '{}'", expected_type.string, return_type.string), state.line));
+				}
+
 				/* call the macro and return its return value */
-				/* expect will only fail if we set up the macro wrong */
-				(macro_obj.function)(state, &args)?.unwrap_or_else(|| panic!("macro {name} returns a value of type '{:?}', but when calling it, it did not return a value", macro_obj.return_type))
+				/* this only panics if we set up the macro wrong */
+				macros::call_macro(state, name, &args)?.unwrap_or_else(|| panic!("macro {name} returns a value of type '{}', but when calling it, it did not return a value", return_type.string))
+			}
+
+			/* the pointer and the length of a slice, like s.len */
+			(Some(Identifier(x)), Some(Operator(Dot))) => {
+				iter.next(); /* strip . */
+
+				let (addr, field) = slice_field(state, x, iter.next())?;
+				if (expected_type.string != "i64") {
+					return Err((format!("expected expression to evaluate to type '{}', but '{x}.{field}' is an i64", expected_type.string), state.line));
+				}
+
+				addr
 			}
 
 			/* variables */
@@ -78,6 +120,10 @@ pub fn eval_expression(state: &mut State, expr: &Expression, expected_type: &Dat
 		})
 	}
 
+	if (expected_type.is_tuple()) {
+		return Err((format!("expected expression to evaluate to the tuple '{}', but tuples cannot be used here", expected_type.string), state.line));
+	}
+
 	let root_register = get_rbx(&expected_type.word);
 	let root_value = eval_miniexpression(state, &mut iter, expected_type)?;
 	
@@ -108,11 +154,12 @@ pub fn eval_expression(state: &mut State, expr: &Expression, expected_type: &Dat
 				let accumulator = get_accumulator(&expected_type.word);
 				let r11 = get_r11(&expected_type.word);
 
-				/* clear out rdx before division (if we dont do this we will Crash the Fucking Program) */
-				state.textsect.push_str("\n\tcdq\n");
-				
-				state.textsect.push_str(&format!("\tmov {r11}, {val}\n"));
+				state.textsect.push_str(&format!("\n\tmov {r11}, {val}\n"));
 				state.textsect.push_str(&format!("\tmov {accumulator}, {root_register}\n"));
+
+				/* sign extend into rdx before division (if we dont do this we will Crash the Fucking Program) */
+				/* it has to happen after the dividend is in the accumulator, and with the size of it, or 64 bit division gets the wrong answer */
+				state.textsect.push_str(&format!("\t{}\n", get_sign_extension(&expected_type.word)));
 				state.textsect.push_str(&format!("\tidiv {r11}\n"));
 
 				state.textsect.push_str(&format!("\tmov {root_register}, {accumulator}\n\n"));
@@ -126,8 +173,37 @@ pub fn eval_expression(state: &mut State, expr: &Expression, expected_type: &Dat
 	Ok(root_register.to_owned())
 }
 
+/* returns the address of s.len or s.ptr, and the name of the field */
+fn slice_field(state: &State, name: &str, field: Option<&Token>) -> Result<(String, String), (String, i64)> {
+	let var = match state.function.local_variables.get(name) {
+		Some(x) => x,
+		None => return Err((format!("variable '{name}' is not defined in the current scope"), state.line))
+	};
+
+	if (var.vartype.element_type(state)?.is_none()) {
+		return Err((format!("only strings and slices have fields, but '{name}' is of type '{}'", var.vartype.string), state.line));
+	}
+
+	match field {
+		Some(Identifier(x)) if x == "ptr" => Ok((var.addr.clone(), x.clone())),
+		Some(Identifier(x)) if x == "len" => Ok((member_address(&var.addr, 8), x.clone())),
+		Some(Identifier(x)) => Err((format!("'{x}' is not a field of '{}', only len and ptr are", var.vartype.string), state.line)),
+		_ => Err((format!("expected 'len' or 'ptr' after '{name}.'"), state.line))
+	}
+}
+
 /* infers a type from an expression */
 pub fn infer_type(state: &mut State, expr: &Expression) -> Result<DataType, (String, i64)> {
+	let expr = &lower_method_calls(state, expr)?;
+
+	if let Some(x) = infer_try_type(state, expr)? {
+		return Ok(x);
+	}
+
+	if let Some(x) = infer_if_type(state, expr)? {
+		return Ok(x);
+	}
+
 	let mut iter = expr.iter();
 	match iter.next() {
 		Some(Identifier(identifier)) => {
@@ -135,6 +211,19 @@ pub fn infer_type(state: &mut State, expr: &Expression) -> Result<DataType, (Str
 			if let Some(Operator(LeftParen)) = iter.next() {
 				/* return function return type */
 				if (!identifier.ends_with('!')) {
+					if (state.generics.contains_key(identifier)) {
+						let args = process_function_parameters(&mut iter);
+
+						return match generic_return_type(state, identifier, &args)? {
+							Some(x) => Ok(x),
+							None => Err((format!("attempted to use return value of function '{identifier}' in expression but it does not return anything"), state.line))
+						};
+					}
+
+					if (identifier == "Ok" || identifier == "Err") {
+						return Err((format!("the type of {identifier}(...) can't be inferred, it has to be written out like 'let x: Result<i64, i64> = {identifier}(...)'"), state.line));
+					}
+
 					match state.functions.get(identifier) {
 						Some(x) => match &x.return_type {
 							Some(x) => return Ok(x.clone()), /* we return here */
@@ -145,12 +234,18 @@ pub fn infer_type(state: &mut State, expr: &Expression) -> Result<DataType, (Str
 				}
 
 				/* return macro return type */
-				match macros::get_macro(state, identifier)?.return_type {
-					Some(x) => Ok(DataType::new(x, state.line)?), /* we return here */
+				let args = process_function_parameters(&mut iter);
+				match macros::macro_return_type(state, identifier, &args)? {
+					Some(x) => Ok(x), /* we return here */
 					None => Err((format!("attempted to use return value of macro '{identifier}' in expression but it does not return anything"), state.line))
 				}
 			}
-			/* variables */
+			/* s.len and s.ptr */
+			else if let Some(Operator(Dot)) = expr.get(1) {
+				slice_field(state, identifier, expr.get(2))?;
+				DataType::new("i64", state.line)
+			}
+			/* variables, and sub-slices of them like s[1..3], which have the same type */
 			else {
 				match state.function.local_variables.get(identifier) {
 					Some(x) => Ok(x.vartype.clone()),
@@ -158,7 +253,23 @@ pub fn infer_type(state: &mut State, expr: &Expression) -> Result<DataType, (Str
 				}
 			}
 		}
+		/* tuple expressions, the type of a tuple is the types of all of its elements */
+		Some(Operator(LeftParen)) => {
+			let mut members: Vec<DataType> = Vec::new();
+			for i in process_function_parameters(&mut iter) {
+				members.push(infer_type(state, &i)?);
+			}
+
+			if (members.len() < 2) {
+				return Err((String::from("tuple expressions must have at least 2 elements"), state.line));
+			}
+
+			Ok(DataType::tuple(members))
+		}
 		Some(Numerical(_)) => DataType::new("i32", state.line),
+		/* -x is the type of x */
+		Some(Operator(Dash)) => infer_type(state, &expr[1..].to_vec()),
+		/* string literals are a pointer to a null terminated string, unless they're used as a str, like in 'let s: str = "..."' */
 		Some(StringLiteral(_)) => DataType::new("i64", state.line),
 		
 		Some(err) => Err((format!("expected an identifier, int literal, or string literal as the first element of expression, but got {err} instead"), state.line)),
@@ -0,0 +1,313 @@
+use super::*;
+
+/* generic functions don't get any code generated for them when they're defined */
+/* instead, every time one gets called with a new set of types we substitute those types into its definition and generate that as a regular function */
+/* these instances get a mangled name, so that max<T> called with an i32 and with an i64 becomes max.i32 and max.i64 */
+
+#[derive(Clone)]
+pub struct GenericFunction {
+	type_params: Vec<String>,
+	/* type paramater -> the traits that the type it gets bound to has to implement */
+	bounds: HashMap<String, Vec<String>>,
+	arg_types: Vec<String>,
+	return_type: Option<String>,
+	definition: AstType,
+	pub public: bool,
+	pub attributes: Vec<Attribute>,
+	pub file: String,
+	line: i64
+}
+
+/* replaces every type paramater in a type string with the type it's bound to, so "(T, i32)" with T bound to i64 becomes "(i64, i32)" */
+pub fn substitute_type(input: &str, bindings: &HashMap<String, String>) -> String {
+	if (input.starts_with('(') && input.ends_with(')')) {
+		let members: Vec<String> = split_tuple_type(&input[1..input.len()-1]).into_iter()
+			.map(|x| substitute_type(x, bindings))
+			.collect();
+
+		return format!("({})", members.join(", "));
+	}
+
+	if (input.starts_with('[') && input.ends_with(']')) {
+		return format!("[{}]", substitute_type(&input[1..input.len()-1], bindings));
+	}
+
+	if (input.starts_with("Result<") && input.ends_with('>')) {
+		let members: Vec<String> = split_tuple_type(&input["Result<".len()..input.len()-1]).into_iter()
+			.map(|x| substitute_type(x, bindings))
+			.collect();
+
+		return format!("Result<{}>", members.join(", "));
+	}
+
+	match bindings.get(input) {
+		Some(x) => x.clone(),
+		None => input.to_owned()
+	}
+}
+
+/* type paramaters can also show up inside of expressions, like in macro calls that take a type */
+fn substitute_expression(input: &Expression, bindings: &HashMap<String, String>) -> Expression {
+	input.iter().map(|x| match x {
+		Identifier(name) if bindings.contains_key(name) => Identifier(bindings[name].clone()),
+		_ => x.clone()
+	}).collect()
+}
+
+pub fn substitute_ast(input: &[AstType], bindings: &HashMap<String, String>) -> Vec<AstType> {
+	input.iter().map(|x| match x {
+		FunctionDefinition(name, public, attributes, type_params, (arg_names, arg_types), return_type, body) => FunctionDefinition(
+			name.clone(),
+			*public,
+			attributes.clone(),
+			type_params.clone(),
+			(arg_names.clone(), arg_types.iter().map(|x| substitute_type(x, bindings)).collect()),
+			return_type.as_ref().map(|x| substitute_type(x, bindings)),
+			substitute_ast(body, bindings)
+		),
+		ReturnStatement(expr) => ReturnStatement(substitute_expression(expr, bindings)),
+		IfStatement(expr1, operator, expr2, body) => IfStatement(
+			substitute_expression(expr1, bindings),
+			operator.clone(),
+			substitute_expression(expr2, bindings),
+			substitute_ast(body, bindings)
+		),
+		VariableDefinition(name, vartype, initexpr) => VariableDefinition(
+			name.clone(),
+			vartype.as_ref().map(|x| substitute_type(x, bindings)),
+			initexpr.as_ref().map(|x| substitute_expression(x, bindings))
+		),
+		TupleDestructuring(names, expr) => TupleDestructuring(names.clone(), substitute_expression(expr, bindings)),
+		VariableAssigment(name, expr) => VariableAssigment(name.clone(), substitute_expression(expr, bindings)),
+		MacroCall(name, args) => MacroCall(name.clone(), args.iter().map(|x| substitute_expression(x, bindings)).collect()),
+		FunctionCall(name, args) => FunctionCall(name.clone(), args.iter().map(|x| substitute_expression(x, bindings)).collect()),
+		MethodCall(expr) => MethodCall(substitute_expression(expr, bindings)),
+		TryStatement(expr) => TryStatement(substitute_expression(expr, bindings)),
+		_ => x.clone()
+	}).collect()
+}
+
+/* turns a type into something that can be used in a symbol name, (i64, i32) becomes $i64_i32$ and [i8] becomes @i8@ */
+pub fn mangle_type(input: &str) -> String {
+	input.replace(", ", "_").replace(' ', "_").replace(['(', ')'], "$").replace(['[', ']'], "@").replace(['<', '>'], "?")
+}
+
+/* returns true if a type string mentions any of the type paramaters */
+fn uses_type_param(input: &str, type_params: &[String]) -> bool {
+	if (input.starts_with('(') && input.ends_with(')')) {
+		return split_tuple_type(&input[1..input.len()-1]).into_iter().any(|x| uses_type_param(x, type_params));
+	}
+
+	if (input.starts_with("Result<") && input.ends_with('>')) {
+		return split_tuple_type(&input["Result<".len()..input.len()-1]).into_iter().any(|x| uses_type_param(x, type_params));
+	}
+
+	type_params.iter().any(|x| x == input)
+}
+
+/* matches the type of a paramater against the type of the argument that was passed to it, and binds any type paramaters in it */
+fn unify(generic: &GenericFunction, param_type: &str, arg_type: &DataType, bindings: &mut HashMap<String, String>, line: i64) -> Result<(), (String, i64)> {
+	if (param_type.starts_with('(') && param_type.ends_with(')')) {
+		let members = split_tuple_type(&param_type[1..param_type.len()-1]);
+
+		/* this mismatch gets reported properly when the instance gets type checked */
+		if (members.len() != arg_type.members.len()) {
+			return Ok(());
+		}
+
+		for (i, v) in members.into_iter().enumerate() {
+			unify(generic, v, &arg_type.members[i], bindings, line)?;
+		}
+
+		return Ok(());
+	}
+
+	/* the types of Ok and Err of a Result, which are the second and the third member of it */
+	if (param_type.starts_with("Result<") && param_type.ends_with('>')) {
+		if (arg_type.result_types().is_none()) {
+			return Ok(());
+		}
+
+		for (i, v) in split_tuple_type(&param_type["Result<".len()..param_type.len()-1]).into_iter().enumerate() {
+			unify(generic, v, &arg_type.members[i + 1], bindings, line)?;
+		}
+
+		return Ok(());
+	}
+
+	if (!generic.type_params.iter().any(|x| x == param_type)) {
+		return Ok(());
+	}
+
+	match bindings.get(param_type) {
+		Some(x) if x != &arg_type.string => Err((format!("conflicting types for type paramater '{param_type}', it is inferred as both '{x}' and '{}'", arg_type.string), line)),
+		Some(_) => Ok(()),
+		None => {
+			bindings.insert(param_type.to_owned(), arg_type.string.clone());
+			Ok(())
+		}
+	}
+}
+
+/* figures out what every type paramater of a generic function is, based on the arguments it gets called with */
+fn infer_bindings(state: &mut State, name: &str, generic: &GenericFunction, args: &[Expression]) -> Result<HashMap<String, String>, (String, i64)> {
+	if (args.len() != generic.arg_types.len()) {
+		return Err((format!("function '{name}' accepts {} arguments but {} {} given", generic.arg_types.len(), args.len(), if (args.len() == 1) {
+			"was"
+		}
+		else {
+			"were"
+		}), state.line))
+	}
+
+	let mut bindings: HashMap<String, String> = HashMap::new();
+
+	/* int literals can be any integer type, so we only let them decide what a type paramater is if nothing else did */
+	/* otherwise max(5, x) with x being an i64 would infer T as i32 */
+	for literals in [false, true] {
+		for (i, v) in args.iter().enumerate() {
+			if (matches!(v.as_slice(), [Numerical(_)]) != literals) {
+				continue;
+			}
+
+			if (literals && bindings.contains_key(&generic.arg_types[i])) {
+				continue;
+			}
+
+			let arg_type = infer_type(state, v)?;
+			unify(generic, &generic.arg_types[i], &arg_type, &mut bindings, state.line)?;
+		}
+	}
+
+	Ok(bindings)
+}
+
+fn mangled_name(name: &str, generic: &GenericFunction, bindings: &HashMap<String, String>) -> String {
+	let type_args: Vec<String> = generic.type_params.iter().map(|x| mangle_type(&bindings[x])).collect();
+	format!("{name}.{}", type_args.join("."))
+}
+
+/* substitutes the bindings into the definition of a generic function and generates it under 'instance_name' */
+/* the instance gets generated as its own function, separately from the function we're currently in the middle of generating */
+/* its code is kept in state.instances until we're done with the current function, and then it gets appended after it */
+fn generate_instance(state: &mut State, instance_name: &str, generic: &GenericFunction, bindings: &HashMap<String, String>) -> Result<(), (String, i64)> {
+	let instance = match substitute_ast(std::slice::from_ref(&generic.definition), bindings).pop() {
+		Some(FunctionDefinition(_, public, attributes, _, args, return_type, body)) => FunctionDefinition(instance_name.to_owned(), public, attributes, Vec::new(), args, return_type, body),
+		_ => unreachable!()
+	};
+
+	let line = state.line;
+	let file = std::mem::replace(&mut state.file, generic.file.clone());
+	let textsect = std::mem::take(&mut state.textsect);
+	let function = std::mem::take(&mut state.function);
+	state.line = generic.line;
+
+	let result = generate(state, std::slice::from_ref(&instance));
+
+	let instance_text = std::mem::replace(&mut state.textsect, textsect);
+	state.function = function;
+	state.line = line;
+	state.file = file;
+
+	result?;
+	state.instances.push_str(&instance_text);
+
+	Ok(())
+}
+
+/* returns the name of the instance of a generic function for the given bindings, and generates it if it doesn't exist yet */
+fn instantiate(state: &mut State, name: &str, generic: &GenericFunction, bindings: &HashMap<String, String>) -> Result<String, (String, i64)> {
+	let instance_name = mangled_name(name, generic, bindings);
+
+	if (!state.functions.contains_key(&instance_name)) {
+		let call_line = state.line;
+
+		if let Err((err, line)) = generate_instance(state, &instance_name, generic, bindings) {
+			let type_args: Vec<&str> = generic.type_params.iter().map(|x| bindings[x].as_str()).collect();
+			let message = format!("{err} (in {name}<{}>, which was instantiated at {}, line {})", type_args.join(", "), state.file, call_line+1);
+
+			/* the error is in the generic function, which might be in a different file than the call */
+			state.file = generic.file.clone();
+			return Err((message, line));
+		}
+	}
+
+	Ok(instance_name)
+}
+
+/* if 'name' is a generic function, instantiates it for the types of the arguments and returns the name of that instance */
+/* if it's a regular function this just returns its name */
+pub fn resolve_function(state: &mut State, name: &str, args: &[Expression]) -> Result<String, (String, i64)> {
+	let generic = match state.generics.get(name) {
+		Some(x) => x.clone(),
+		None => return Ok(name.to_owned())
+	};
+
+	let bindings = infer_bindings(state, name, &generic, args)?;
+
+	for (type_param, bounds) in &generic.bounds {
+		check_bounds(state, name, type_param, &bindings[type_param], bounds)?;
+	}
+
+	instantiate(state, name, &generic, &bindings)
+}
+
+/* returns the return type that a generic function would have when called with these arguments, without instantiating it */
+pub fn generic_return_type(state: &mut State, name: &str, args: &[Expression]) -> Result<Option<DataType>, (String, i64)> {
+	/* unwrap will never fail, the caller checks if the function is generic */
+	let generic = state.generics.get(name).unwrap().clone();
+	let bindings = infer_bindings(state, name, &generic, args)?;
+
+	match &generic.return_type {
+		Some(x) => Ok(Some(resolve_type(state, &substitute_type(x, &bindings))?)),
+		None => Ok(None)
+	}
+}
+
+/* registers a generic function and type checks it */
+pub fn define_generic(state: &mut State, definition: &AstType) -> Result<(), (String, i64)> {
+	let (name, public, attributes, type_params, arg_types, return_type) = match definition {
+		FunctionDefinition(name, public, attributes, type_params, (_, arg_types), return_type, _) => (name, public, attributes, type_params, arg_types, return_type),
+		_ => unreachable!()
+	};
+
+	let names: Vec<String> = type_params.iter().map(|x| x.0.clone()).collect();
+
+	for i in &names {
+		/* a type paramater that only shows up in the return type (or nowhere) could never be inferred */
+		if (!arg_types.iter().any(|x| uses_type_param(x, std::slice::from_ref(i)))) {
+			return Err((format!("type paramater '{i}' of function '{name}' is not used by any of its paramaters, so it can never be inferred"), state.line));
+		}
+	}
+
+	let generic = GenericFunction {
+		type_params: names.clone(),
+		bounds: type_params.iter().filter(|x| !x.1.is_empty()).cloned().collect(),
+		arg_types: arg_types.clone(),
+		return_type: return_type.clone(),
+		definition: definition.clone(),
+		public: *public,
+		attributes: attributes.clone(),
+		file: state.file.clone(),
+		line: state.line
+	};
+
+	state.generics.insert(name.clone(), generic.clone());
+
+	/* we type check the function by generating it once with every type paramater bound to a placeholder type */
+	/* a placeholder type can only be used where that exact type paramater is expected, so 'let x: i32 = a' with 'a: T' gets caught here */
+	/* this is done on a copy of the state so that none of the generated code ends up in the output */
+	let mut scratch = state.clone();
+	let bindings: HashMap<String, String> = names.iter().map(|x| (x.clone(), format!("<{x}>"))).collect();
+
+	/* the placeholders only have the methods of the traits that their type paramater requires */
+	for (type_param, bounds) in &generic.bounds {
+		add_placeholder_methods(&mut scratch, &bindings[type_param], bounds)?;
+	}
+
+	if let Err((err, line)) = generate_instance(&mut scratch, &mangled_name(name, &generic, &bindings), &generic, &bindings) {
+		return Err((format!("{err} (in generic function {name}<{}>)", names.join(", ")), line));
+	}
+
+	Ok(())
+}
@@ -0,0 +1,101 @@
+use super::*;
+
+/* functions with #[inline] get their body inserted wherever they're called instead of being called */
+/* they still get generated as a regular function too, so they can be called recursively or from other object files */
+
+/* everything we need to insert the body of an inline function somewhere else */
+#[derive(Clone)]
+pub struct InlineBody {
+	pub arg_names: Vec<String>,
+	pub body: BlockStatement,
+	pub file: String,
+	pub line: i64
+}
+
+/* returns true if a call to 'name' should be inlined */
+/* recursive calls can't be, otherwise we would keep inlining the function into itself forever */
+pub fn should_inline(state: &State, name: &str, function: &Function) -> bool {
+	function.inline.is_some() && name != state.function.name && !state.function.inlining.iter().any(|x| x == name)
+}
+
+/* inserts the body of an inline function at the current position, this returns the same thing as call_function */
+pub fn inline_call(state: &mut State, name: &str, function: &Function, args: &[Expression]) -> Result<(Option<DataType>, Option<String>), (String, i64)> {
+	/* unwrap will never fail, should_inline checks that the function has a body to inline */
+	let inline = function.inline.clone().unwrap();
+
+	/* the arguments get evaluated into new stack slots, which become the paramaters of the inlined body */
+	/* they're all evaluated before any of them are visible, so f(b, a) with paramaters (a, b) doesn't mix them up */
+	let mut params: HashMap<String, Variable> = HashMap::new();
+	for (i, v) in args.iter().enumerate() {
+		let vartype = resolve_type(state, &function.arg_types[i])?;
+
+		let addr = if (vartype.is_tuple()) {
+			let value = eval_tuple(state, v, &vartype)?;
+			let addr = reserve_stack(state, &vartype);
+			copy_tuple(state, &value, &addr, &vartype);
+			addr
+		}
+		else {
+			let addr = reserve_stack(state, &vartype);
+			store_expression(state, v, &vartype, &addr)?;
+			addr
+		};
+
+		params.insert(inline.arg_names[i].clone(), Variable { addr, vartype });
+	}
+
+	/* rbx is callee saved, and the expression we're in the middle of might be using it */
+	let rbx = reserve_stack(state, &DataType::new("i64", state.line)?);
+	state.textsect.push_str(&format!("\tmov qword {rbx}, rbx\n"));
+
+	/* big tuples get returned through memory, the return statements of the body write them through return_pointer */
+	let return_buffer = match &function.return_type {
+		Some(x) if classify(x) == PassingClass::Memory => Some(reserve_stack(state, x)),
+		_ => None
+	};
+
+	let return_pointer = match &return_buffer {
+		Some(buffer) => {
+			let addr = reserve_stack(state, &DataType::new("i64", state.line)?);
+			state.textsect.push_str(&format!("\tlea rax, {buffer}\n"));
+			state.textsect.push_str(&format!("\tmov qword {addr}, rax\n"));
+			Some(addr)
+		}
+		None => None
+	};
+
+	state.labels += 1;
+	let end_label = format!(".L{}", state.labels);
+
+	/* the body gets generated in its own scope, as if we were inside of the inlined function */
+	let caller = state.function.clone();
+	state.function.local_variables = params;
+	state.function.return_type = function.return_type.clone();
+	state.function.return_pointer = return_pointer;
+	state.function.noreturn = function.attributes.contains(&Attribute::NoReturn);
+	state.function.inline_return = Some(end_label.clone());
+	state.function.inlining.push(name.to_owned());
+
+	let call_line = std::mem::replace(&mut state.line, inline.line);
+	let call_file = std::mem::replace(&mut state.file, inline.file.clone());
+
+	if let Err((err, line)) = generate(state, &inline.body) {
+		return Err((format!("{err} (in the body of '{name}', which was inlined at {call_file}, line {})", call_line+1), line));
+	}
+
+	state.line = call_line;
+	state.file = call_file;
+
+	/* the stack frame is shared with the caller, so everything the body reserved has to stay reserved */
+	state.function = CurrentFunctionState {
+		stacksize: state.function.stacksize,
+		stackspace: state.function.stackspace,
+		calls_funcs: state.function.calls_funcs,
+		..caller
+	};
+
+	state.textsect.push_str(&format!("{end_label}:\n"));
+	state.textsect.push_str(&format!("\tmov rbx, qword {rbx}\n"));
+
+	Ok((function.return_type.clone(), return_buffer))
+}
@@ -1,4 +1,7 @@
 use crate::lexer;
+use crate::parser::{parse_type, comparison_operator};
+use std::fs;
+use std::path::{Path, PathBuf};
 use super::*;
 
 type MacroDefinition = fn(&mut State, &[Expression]) -> Result<Option<String>, (String, i64)>;
@@ -9,23 +12,137 @@ pub struct Macro {
 }
 
 pub fn get_macro(state: &State, macro_name: &str) -> Result<Macro, (String, i64)> {
-	Ok(match macro_name {
+	match builtin_macro(macro_name) {
+		Some(x) => Ok(x),
+		None => Err((format!("macro '{}' does not exist", macro_name), state.line))
+	}
+}
+
+/* the preprocessor uses this so that user defined macros can't have the name of one of these */
+pub fn is_builtin_macro(macro_name: &str) -> bool {
+	builtin_macro(macro_name).is_some()
+}
+
+fn builtin_macro(macro_name: &str) -> Option<Macro> {
+	Some(match macro_name {
 		"asm!" => Macro {
 			return_type: None,
 			function: asm
 		},
+		"global_asm!" => Macro {
+			return_type: None,
+			function: global_asm
+		},
 		"syscall!" => Macro {
 			return_type: Some("i64"),
 			function: syscall
 		},
+		"errno_str!" => Macro {
+			return_type: Some("str"),
+			function: errno_str
+		},
 		"typeof!" => Macro {
 			return_type: Some("i64"),
 			function: typeof_
 		},
-		_ => return Err((format!("macro '{}' does not exist", macro_name), state.line))
+		"sizeof!" => Macro {
+			return_type: Some("i64"),
+			function: sizeof
+		},
+		"alignof!" => Macro {
+			return_type: Some("i64"),
+			function: alignof
+		},
+		"include_str!" => Macro {
+			return_type: Some("str"),
+			function: include_str
+		},
+		"include_bytes!" => Macro {
+			return_type: Some("[i8]"),
+			function: include_bytes
+		},
+		"panic!" => Macro {
+			return_type: None,
+			function: panic
+		},
+		"assert!" => Macro {
+			return_type: None,
+			function: assert
+		},
+		"print!" => Macro {
+			return_type: None,
+			function: print
+		},
+		"println!" => Macro {
+			return_type: None,
+			function: println
+		},
+		"alloc!" => Macro {
+			return_type: Some("i64"),
+			function: alloc
+		},
+		"realloc!" => Macro {
+			return_type: Some("i64"),
+			function: realloc
+		},
+		"free!" => Macro {
+			return_type: None,
+			function: free
+		},
+		/* the return type of dbg! is the type of its argument, see macro_return_type */
+		"dbg!" => Macro {
+			return_type: None,
+			function: dbg
+		},
+		"is_ok!" => Macro {
+			return_type: Some("i64"),
+			function: is_ok
+		},
+		"is_err!" => Macro {
+			return_type: Some("i64"),
+			function: is_err
+		},
+		/* these return the type of Ok or Err of their argument, see macro_return_type */
+		"unwrap!" => Macro {
+			return_type: None,
+			function: unwrap
+		},
+		"unwrap_err!" => Macro {
+			return_type: None,
+			function: unwrap_err
+		},
+		_ => return None
 	})
 }
 
+/* returns the type of the value that a call to a macro evaluates to */
+pub fn macro_return_type(state: &mut State, macro_name: &str, args: &[Expression]) -> Result<Option<DataType>, (String, i64)> {
+	if (macro_name == "dbg!") {
+		return match args {
+			[x] => Ok(Some(infer_type(state, x)?)),
+			_ => Err((format!("dbg! macro accepts 1 argument, not {}", args.len()), state.line))
+		};
+	}
+
+	if (macro_name == "unwrap!" || macro_name == "unwrap_err!") {
+		let result_type = match args {
+			[x] => infer_type(state, x)?,
+			_ => return Err((format!("{macro_name} macro accepts 1 argument, not {}", args.len()), state.line))
+		};
+
+		return match result_type.result_types() {
+			Some((ok, _)) if macro_name == "unwrap!" => Ok(Some(ok.clone())),
+			Some((_, err)) => Ok(Some(err.clone())),
+			None => Err((format!("the argument of {macro_name} has to be a Result, but it was given a value of type '{}'", result_type.string), state.line))
+		};
+	}
+
+	match get_macro(state, macro_name)?.return_type {
+		Some(x) => Ok(Some(resolve_type(state, x)?)),
+		None => Ok(None)
+	}
+}
+
 pub fn call_macro(state: &mut State, macro_name: &str, args: &[Expression]) -> Result<Option<String>, (String, i64)> {
 	let function_ptr = get_macro(state, macro_name)?.function;
 	function_ptr(state, args)
@@ -52,13 +169,650 @@ fn typeof_(state: &mut State, args: &[Expression]) -> Result<Option<String>, (St
 	Ok(Some(to_return))
 }
 
+/* ------------------------------ */
+/*      sizeof! and alignof!      */
+/* ------------------------------ */
+/* these are evaluated at compile time, so they just return a number that gets used like any other int literal */
+
+/* the argument can either be a type, like sizeof!(i32) or sizeof!((i64, i8)), or an expression, like sizeof!(x) */
+fn type_of_argument(state: &mut State, name: &str, args: &[Expression]) -> Result<DataType, (String, i64)> {
+	if (args.len() != 1) {
+		return Err((format!("{name} macro accepts 1 argument, not {}", args.len()), state.line))
+	}
+
+	let mut iter = args[0].iter();
+	let type_string = match (parse_type(&mut iter), iter.next()) {
+		(Some(x), None) => x,
+		_ => return infer_type(state, &args[0])
+	};
+
+	/* a variable with the same name as a type takes priority, the same way it would in an expression */
+	if (state.function.local_variables.contains_key(&type_string)) {
+		return infer_type(state, &args[0]);
+	}
+
+	match resolve_type(state, &type_string) {
+		Ok(x) => Ok(x),
+		/* something like (a, b) looks like a tuple type, but it could also be a tuple of variables */
+		Err(err) if args[0].len() > 1 => infer_type(state, &args[0]).map_err(|_| err),
+		Err(err) => Err(err)
+	}
+}
+
+fn sizeof(state: &mut State, args: &[Expression]) -> Result<Option<String>, (String, i64)> {
+	Ok(Some(type_of_argument(state, "sizeof!", args)?.byte_size.to_string()))
+}
+
+fn alignof(state: &mut State, args: &[Expression]) -> Result<Option<String>, (String, i64)> {
+	Ok(Some(type_of_argument(state, "alignof!", args)?.alignment().to_string()))
+}
+
+/* ------------------------------------------ */
+/*      include_str! and include_bytes!       */
+/* ------------------------------------------ */
+/* these embed a file into the binary at compile time, include_str! returns it as a str and include_bytes! as an [i8] */
+
+/* reads the file that gets included, paths are relative to the file that's being compiled */
+fn read_include(state: &State, name: &str, args: &[Expression]) -> Result<(String, Vec<u8>), (String, i64)> {
+	let path = match args {
+		[x] => match x.as_slice() {
+			[StringLiteral(x)] => x,
+			_ => return Err((format!("{name} expects the path of a file as a string literal"), state.line))
+		},
+		_ => return Err((format!("{name} macro accepts 1 argument, not {}", args.len()), state.line))
+	};
+
+	let full_path = match Path::new(&state.file).parent() {
+		Some(x) => x.join(path),
+		None => PathBuf::from(path)
+	};
+
+	match fs::read(&full_path) {
+		Ok(x) => Ok((path.clone(), x)),
+		Err(err) => Err((format!("{name} could not read '{path}' (looked for it at {}) [{err}]", full_path.display()), state.line))
+	}
+}
+
+/* writes the pointer and the length into a new slice and returns its address */
+fn pointer_and_length(state: &mut State, slice_type: &str, label: &str, length: usize) -> Result<Option<String>, (String, i64)> {
+	let addr = reserve_stack(state, &DataType::new(slice_type, state.line)?);
+
+	state.textsect.push_str(&format!("\tmov rax, {label}\n"));
+	state.textsect.push_str(&format!("\tmov qword {addr}, rax\n"));
+	state.textsect.push_str(&format!("\tmov qword {}, {length}\n", member_address(&addr, 8)));
+
+	Ok(Some(addr))
+}
+
+fn include_str(state: &mut State, args: &[Expression]) -> Result<Option<String>, (String, i64)> {
+	let (path, mut bytes) = read_include(state, "include_str!", args)?;
+
+	if (std::str::from_utf8(&bytes).is_err()) {
+		return Err((format!("include_str! can only include text files, but '{path}' is not valid utf-8, use include_bytes! instead"), state.line));
+	}
+
+	/* strings get a null terminator, so that they can be passed to c functions, but it doesn't count towards the length */
+	let length = bytes.len();
+	bytes.push(0);
+
+	let label = resolve_rodata(state, &bytes);
+	pointer_and_length(state, "str", &label, length)
+}
+
+fn include_bytes(state: &mut State, args: &[Expression]) -> Result<Option<String>, (String, i64)> {
+	let (_, bytes) = read_include(state, "include_bytes!", args)?;
+
+	let label = resolve_rodata(state, &bytes);
+	pointer_and_length(state, "[i8]", &label, bytes.len())
+}
+
+/* ------------------------------ */
+/*      panic! and assert!        */
+/* ------------------------------ */
+/* these print 'file:line: message' to stderr and then exit with status 101 (or execute ud2 with --panic=trap) */
+/* they only use syscalls, so they work without libc too */
+
+/* writes the code that prints the message and stops the program */
+pub fn emit_panic(state: &mut State, message: &str) {
+	/* the message goes into .rodata as bytes, so nasm won't handle the escape sequences in it */
+	let message = format!("{}:{}: {}\n", state.file, state.line+1, unescape(message));
+	let label = resolve_rodata(state, message.as_bytes());
+
+	/* write(2, message, length) */
+	state.textsect.push_str("\tmov rax, 1\n");
+	state.textsect.push_str("\tmov rdi, 2\n");
+	state.textsect.push_str(&format!("\tmov rsi, {label}\n"));
+	state.textsect.push_str(&format!("\tmov rdx, {}\n", message.len()));
+	state.textsect.push_str("\tsyscall\n");
+
+	match state.panic {
+		PanicStrategy::Abort => {
+			/* exit(101) */
+			state.textsect.push_str("\tmov rax, 60\n");
+			state.textsect.push_str("\tmov rdi, 101\n");
+			state.textsect.push_str("\tsyscall\n\n");
+		}
+		PanicStrategy::Trap => state.textsect.push_str("\tud2\n\n")
+	}
+}
+
+/* gets the message out of the argument of panic! or the second argument of assert! */
+fn panic_message<'a>(state: &State, name: &str, arg: &'a Expression) -> Result<&'a str, (String, i64)> {
+	match arg.as_slice() {
+		[StringLiteral(x)] => Ok(x),
+		_ => Err((format!("the message of {name} must be a string literal"), state.line))
+	}
+}
+
+/* turns the tokens of an expression back into something that looks like the source code, like 'x * f(a, b)' */
+/* used for the default message of assert! and by dbg! */
+fn expression_source(expr: &Expression) -> String {
+	let mut output = String::new();
+	let mut prev: Option<&Token> = None;
+
+	for i in expr {
+		/* no spaces inside of parentheses, before commas, or between the name of a function and its arguments */
+		let space = !matches!((prev, i),
+			(None, _) | (Some(Operator(LeftParen)), _) |
+			(_, Operator(RightParen) | Operator(Comma)) |
+			(Some(Identifier(_)), Operator(LeftParen))
+		);
+
+		if (space) {
+			output.push(' ');
+		}
+
+		match i {
+			Identifier(x) | Numerical(x) => output.push_str(x),
+			StringLiteral(x) => output.push_str(&format!("\"{x}\"")),
+			Operator(x) => output.push_str(&x.to_string()),
+			Keyword(x) => output.push_str(&format!("{:?}", x).to_lowercase()),
+			Token::Newline => ()
+		}
+
+		prev = Some(i);
+	}
+
+	output
+}
+
+fn panic(state: &mut State, args: &[Expression]) -> Result<Option<String>, (String, i64)> {
+	let message = match args {
+		[] => "explicit panic",
+		[x] => panic_message(state, "panic!", x)?,
+		_ => return Err((format!("panic! macro accepts 1 argument, not {}", args.len()), state.line))
+	};
+
+	emit_panic(state, message);
+
+	Ok(None)
+}
+
+fn assert(state: &mut State, args: &[Expression]) -> Result<Option<String>, (String, i64)> {
+	let condition = match args.first() {
+		Some(x) if !x.is_empty() => x,
+		_ => return Err(("assert! macro expects a condition, like assert!(x == 5, \"x should be 5\")".to_owned(), state.line))
+	};
+
+	let message = match args {
+		[_] => format!("assertion failed: {}", expression_source(condition)),
+		[_, x] => panic_message(state, "assert!", x)?.to_owned(),
+		_ => return Err((format!("assert! macro accepts 1 or 2 arguments, not {}", args.len()), state.line))
+	};
+
+	/* a condition without a comparison, like assert!(x), passes if it isn't 0 */
+	let (expr1, operator, expr2) = match condition.iter().position(|x| comparison_operator(x).is_some()) {
+		Some(i) => (condition[..i].to_vec(), comparison_operator(&condition[i]).unwrap(), condition[i+1..].to_vec()),
+		None => (condition.clone(), ComparisonOperator::NotEqual, vec![Numerical("0".to_owned())])
+	};
+
+	compare(state, &expr1, &expr2)?;
+
+	state.labels += 1;
+	let label = format!(".L{}", state.labels);
+	state.textsect.push_str(&format!("\t{} {label}\n", jump_instruction(&operator, true)));
+
+	emit_panic(state, &message);
+	state.textsect.push_str(&format!("{label}:\n"));
+
+	Ok(None)
+}
+
+/* ------------------------------ */
+/*      print! and println!       */
+/* ------------------------------ */
+/* these write to stdout, like print!("x = {}, name = {s}\n", x, name) */
+/* the format string is split up at compile time, the text between the placeholders gets written as is */
+/* and the arguments get written by the integer and string writers in runtime.rs */
+/* placeholders: */
+/*   {}  - an integer (any of i8, i16, i32 or i64), a str, or a string literal */
+/*   {s} - a pointer to a null terminated string (i64), or a string literal */
+/* {{ and }} write { and } */
+
+#[derive(PartialEq)]
+enum Placeholder {
+	Integer,
+	String
+}
+
+enum FormatPiece {
+	Text(String),
+	Placeholder(Placeholder)
+}
+
+fn parse_format_string(state: &State, name: &str, input: &str) -> Result<Vec<FormatPiece>, (String, i64)> {
+	let mut pieces = Vec::new();
+	let mut text = String::new();
+	let mut iter = input.chars().peekable();
+
+	while let Some(i) = iter.next() {
+		match i {
+			'{' if iter.peek() == Some(&'{') => {
+				iter.next();
+				text.push('{');
+			}
+			'}' if iter.peek() == Some(&'}') => {
+				iter.next();
+				text.push('}');
+			}
+			'{' => {
+				let mut spec = String::new();
+				loop {
+					match iter.next() {
+						Some('}') => break,
+						Some(x) => spec.push(x),
+						None => return Err((format!("expected '}}' to close the placeholder '{{{spec}' in the format string of {name}, use '{{{{' to write a '{{'"), state.line))
+					}
+				}
+
+				let placeholder = match spec.as_str() {
+					"" => Placeholder::Integer,
+					"s" => Placeholder::String,
+					_ => return Err((format!("'{{{spec}}}' is not a valid placeholder in the format string of {name}, expected '{{}}' or '{{s}}'"), state.line))
+				};
+
+				pieces.push(FormatPiece::Text(std::mem::take(&mut text)));
+				pieces.push(FormatPiece::Placeholder(placeholder));
+			}
+			'}' => return Err((format!("unmatched '}}' in the format string of {name}, use '}}}}' to write a '}}'"), state.line)),
+			_ => text.push(i)
+		}
+	}
+
+	pieces.push(FormatPiece::Text(text));
+
+	Ok(pieces)
+}
+
+/* writes the code that writes some text to a file descriptor */
+fn write_text(state: &mut State, fd: i64, text: &str) {
+	if (text.is_empty()) {
+		return;
+	}
+
+	let label = resolve_rodata(state, text.as_bytes());
+	state.textsect.push_str(&format!("\tmov rdi, {fd}\n"));
+	state.textsect.push_str(&format!("\tmov rsi, {label}\n"));
+	state.textsect.push_str(&format!("\tmov rdx, {}\n", text.len()));
+	call_routine(state, "catlang_write");
+}
+
+/* moves an integer into a 64 bit register, smaller integers get sign extended */
+fn load_integer(state: &mut State, register: &str, value: &str, datatype: &DataType) {
+	let instruction = match datatype.word {
+		WordType::QuadWord => "mov",
+		_ if !value.starts_with('[') && !is_register(value) => "mov",
+		WordType::DoubleWord => "movsxd",
+		_ => "movsx"
+	};
+
+	if (value.starts_with('[')) {
+		state.textsect.push_str(&format!("\t{instruction} {register}, {} {value}\n", datatype.word));
+	}
+	else {
+		state.textsect.push_str(&format!("\t{instruction} {register}, {value}\n"));
+	}
+}
+
+fn format(state: &mut State, name: &str, args: &[Expression], newline: bool) -> Result<Option<String>, (String, i64)> {
+	let format_string = match args.first().map(|x| x.as_slice()) {
+		Some([StringLiteral(x)]) => unescape(x),
+		_ => return Err((format!("{name} expects a format string literal as its first argument, like {name}(\"x = {{}}\", x)"), state.line))
+	};
+
+	let pieces = parse_format_string(state, name, &format_string)?;
+	let args = &args[1..];
+
+	let placeholders = pieces.iter().filter(|x| matches!(x, FormatPiece::Placeholder(_))).count();
+	if (placeholders != args.len()) {
+		return Err((format!("the format string of {name} has {placeholders} placeholder{} but {} argument{} given", if (placeholders == 1) { "" } else { "s" }, args.len(), if (args.len() == 1) {
+			" was"
+		}
+		else {
+			"s were"
+		}), state.line));
+	}
+
+	/* text that hasn't been written yet, so that string literal arguments and the text around them get written all at once */
+	let mut text = String::new();
+	let mut args = args.iter().enumerate();
+
+	for i in pieces {
+		let placeholder = match i {
+			FormatPiece::Text(x) => {
+				text.push_str(&x);
+				continue;
+			}
+			FormatPiece::Placeholder(x) => x
+		};
+
+		/* unwrap will never fail, we checked that there's an argument for every placeholder */
+		let (index, arg) = args.next().unwrap();
+
+		/* string literals are known at compile time, so they just become part of the text */
+		if let [StringLiteral(x)] = arg.as_slice() {
+			text.push_str(&unescape(x));
+			continue;
+		}
+
+		write_text(state, 1, &std::mem::take(&mut text));
+
+		let argtype = infer_type(state, arg)?;
+		match (&placeholder, argtype.string.as_str()) {
+			/* strings already know their length, so they get written directly */
+			(Placeholder::Integer, "str") => {
+				let addr = eval_tuple(state, arg, &argtype)?;
+				state.textsect.push_str("\tmov rdi, 1\n");
+				state.textsect.push_str(&format!("\tmov rsi, qword {addr}\n"));
+				state.textsect.push_str(&format!("\tmov rdx, qword {}\n", member_address(&addr, 8)));
+				call_routine(state, "catlang_write");
+				continue;
+			}
+			(Placeholder::Integer, "i8" | "i16" | "i32" | "i64") => (),
+			(Placeholder::String, "i64") => (),
+			(Placeholder::Integer, x) => return Err((format!("argument {} of {name} is of type '{x}', but '{{}}' expects an integer or a str", index+1), state.line)),
+			(Placeholder::String, x) => return Err((format!("argument {} of {name} is of type '{x}', but '{{s}}' expects a pointer to a string (i64)", index+1), state.line))
+		}
+
+		let value = eval_expression(state, arg, &argtype)?;
+		load_integer(state, "rsi", &value, &argtype);
+		state.textsect.push_str("\tmov rdi, 1\n");
+
+		match placeholder {
+			Placeholder::Integer => call_routine(state, "catlang_write_int"),
+			Placeholder::String => call_routine(state, "catlang_write_cstr")
+		}
+	}
+
+	if (newline) {
+		text.push('\n');
+	}
+	write_text(state, 1, &text);
+
+	Ok(None)
+}
+
+fn print(state: &mut State, args: &[Expression]) -> Result<Option<String>, (String, i64)> {
+	format(state, "print!", args, false)
+}
+
+fn println(state: &mut State, args: &[Expression]) -> Result<Option<String>, (String, i64)> {
+	format(state, "println!", args, true)
+}
+
+/* ------------------------------------ */
+/*      alloc!, realloc! and free!      */
+/* ------------------------------------ */
+/* heap memory, from the allocator in runtime.rs, which works the same with and without libc */
+/* alloc!(T, n) returns a pointer to room for n values of type T, or 0 if there isn't enough memory */
+/* realloc!(pointer, T, n) resizes it to n values of type T, keeping what was in it, and free!(pointer) gives it back */
+
+/* the size of the values in alloc! and realloc! */
+fn element_size(state: &State, name: &str, arg: &Expression) -> Result<i32, (String, i64)> {
+	let mut iter = arg.iter();
+	match (parse_type(&mut iter), iter.next()) {
+		(Some(x), None) => Ok(resolve_type(state, &x)?.byte_size),
+		_ => Err((format!("expected a type like i32 or (i64, i8) as the type of the values in call to {name} macro"), state.line))
+	}
+}
+
+/* evaluates the amount of bytes that n values take up into rsi */
+/* panics if n is negative or the size doesn't fit in an i64, instead of asking for a lot less memory than what the values need */
+fn byte_count(state: &mut State, name: &str, count: &Expression, size: i32) -> Result<(), (String, i64)> {
+	let value = eval_expression(state, count, &DataType::new("i64", state.line)?)?;
+
+	state.labels += 2;
+	let (invalid, valid) = (format!(".L{}", state.labels-1), format!(".L{}", state.labels));
+
+	state.textsect.push_str(&format!("\tmov rsi, {value}\n"));
+	state.textsect.push_str("\ttest rsi, rsi\n");
+	state.textsect.push_str(&format!("\tjs {invalid}\n"));
+	state.textsect.push_str(&format!("\timul rsi, rsi, {size}\n"));
+	state.textsect.push_str(&format!("\tjno {valid}\n"));
+	state.textsect.push_str(&format!("{invalid}:\n"));
+	emit_panic(state, &format!("the amount of values in {name} is negative, or they take up more memory than fits in an i64"));
+	state.textsect.push_str(&format!("{valid}:\n"));
+
+	Ok(())
+}
+
+fn alloc(state: &mut State, args: &[Expression]) -> Result<Option<String>, (String, i64)> {
+	let (datatype, count) = match args {
+		[x, y] => (x, y),
+		_ => return Err((format!("alloc! macro accepts 2 arguments (a type and an amount), not {}", args.len()), state.line))
+	};
+
+	let size = element_size(state, "alloc!", datatype)?;
+	byte_count(state, "alloc!", count, size)?;
+
+	state.textsect.push_str("\tmov rdi, rsi\n");
+	call_routine(state, "catlang_alloc");
+
+	Ok(Some(String::from("rax")))
+}
+
+fn realloc(state: &mut State, args: &[Expression]) -> Result<Option<String>, (String, i64)> {
+	let (pointer, datatype, count) = match args {
+		[x, y, z] => (x, y, z),
+		_ => return Err((format!("realloc! macro accepts 3 arguments (a pointer, a type and an amount), not {}", args.len()), state.line))
+	};
+
+	let size = element_size(state, "realloc!", datatype)?;
+
+	/* the pointer gets put on the stack, so that evaluating the amount doesn't overwrite it */
+	let i64 = DataType::new("i64", state.line)?;
+	let addr = reserve_stack(state, &i64);
+	store_expression(state, pointer, &i64, &addr)?;
+
+	byte_count(state, "realloc!", count, size)?;
+	state.textsect.push_str(&format!("\tmov rdi, qword {addr}\n"));
+	call_routine(state, "catlang_realloc");
+
+	Ok(Some(String::from("rax")))
+}
+
+fn free(state: &mut State, args: &[Expression]) -> Result<Option<String>, (String, i64)> {
+	let pointer = match args {
+		[x] => x,
+		_ => return Err((format!("free! macro accepts 1 argument, not {}", args.len()), state.line))
+	};
+
+	let value = eval_expression(state, pointer, &DataType::new("i64", state.line)?)?;
+	state.textsect.push_str(&format!("\tmov rdi, {value}\n"));
+	call_routine(state, "catlang_free");
+
+	Ok(None)
+}
+
+/* --------------- */
+/*      dbg!       */
+/* --------------- */
+/* prints '[file:line] expression = value : type' to stderr, and evaluates to the value */
+/* with --release nothing gets printed, it just evaluates to the value */
+fn dbg(state: &mut State, args: &[Expression]) -> Result<Option<String>, (String, i64)> {
+	let expr = match args {
+		[x] if !x.is_empty() => x,
+		_ => return Err((format!("dbg! macro accepts 1 argument, not {}", args.len()), state.line))
+	};
+
+	let datatype = infer_type(state, expr)?;
+	if (datatype.is_tuple()) {
+		return Err((format!("dbg! can only print integers, but '{}' is of type '{}'", expression_source(expr), datatype.string), state.line));
+	}
+
+	/* the value gets stored on the stack, so that printing it doesn't overwrite it */
+	let addr = reserve_stack(state, &datatype);
+	store_expression(state, expr, &datatype, &addr)?;
+
+	if (state.release) {
+		return Ok(Some(addr));
+	}
+
+	let header = format!("[{}:{}] {} = ", state.file, state.line+1, expression_source(expr));
+	write_text(state, 2, &header);
+
+	load_integer(state, "rsi", &addr, &datatype);
+	state.textsect.push_str("\tmov rdi, 2\n");
+	call_routine(state, "catlang_write_int");
+
+	write_text(state, 2, &format!(" : {}\n", datatype.string));
+
+	Ok(Some(addr))
+}
+
+/* ---------------------------------------------- */
+/*      is_ok!, is_err!, unwrap! and unwrap_err!      */
+/* ---------------------------------------------- */
+/* these deal with the error of a Result, instead of returning it with ? (see results.rs) */
+/* is_ok!(r) and is_err!(r) evaluate to 1 or 0, unwrap!(r) evaluates to the value if it's Ok and panics if it isn't, and unwrap_err!(r) is the opposite */
+
+fn is_ok(state: &mut State, args: &[Expression]) -> Result<Option<String>, (String, i64)> {
+	let (addr, _) = eval_result_argument(state, "is_ok!", args)?;
+
+	/* Ok is 0 and Err is 1 */
+	state.textsect.push_str("\tmov rax, 1\n");
+	state.textsect.push_str(&format!("\tsub rax, qword {addr}\n"));
+
+	Ok(Some("rax".to_owned()))
+}
+
+fn is_err(state: &mut State, args: &[Expression]) -> Result<Option<String>, (String, i64)> {
+	let (addr, _) = eval_result_argument(state, "is_err!", args)?;
+	state.textsect.push_str(&format!("\tmov rax, qword {addr}\n"));
+
+	Ok(Some("rax".to_owned()))
+}
+
+/* returns the address of the value of Ok (which is member 1 of the Result) or the error of Err (member 2), and panics if it's the other one */
+fn unwrap_member(state: &mut State, name: &str, args: &[Expression], member: usize) -> Result<Option<String>, (String, i64)> {
+	let (addr, result_type) = eval_result_argument(state, name, args)?;
+
+	state.labels += 1;
+	let label = format!(".L{}", state.labels);
+
+	state.textsect.push_str(&format!("\tcmp qword {addr}, {}\n", member - 1));
+	state.textsect.push_str(&format!("\tje {label}\n"));
+	emit_panic(state, &format!("called {name} on {}", if (member == 1) {
+		"an Err"
+	}
+	else {
+		"an Ok"
+	}));
+	state.textsect.push_str(&format!("{label}:\n"));
+
+	Ok(Some(member_address(&addr, result_type.member_offsets()[member])))
+}
+
+fn unwrap(state: &mut State, args: &[Expression]) -> Result<Option<String>, (String, i64)> {
+	unwrap_member(state, "unwrap!", args, 1)
+}
+
+fn unwrap_err(state: &mut State, args: &[Expression]) -> Result<Option<String>, (String, i64)> {
+	unwrap_member(state, "unwrap_err!", args, 2)
+}
+
 /* -------------- */
 /*      asm!      */
 /* -------------- */
-fn parse_asm(state: &State, input: &str) -> Result<String, (String, i64)> {
+/* asm!("add {out}, {a}", a = in(reg) x, out = out(reg) y, clobber("rcx")) */
+/* every string literal at the start is a line of assembly, and after them come the operands: */
+/*   name = in(reg) expr   - the value of expr gets put in a register before the asm */
+/*   name = out(reg) var   - the register gets stored in var after the asm */
+/*   name = inout(reg) var - both */
+/* (reg) lets the compiler pick the register, a specific one can be given with something like in("rcx") */
+/* clobber("rcx", "rdx") lists the registers that the asm overwrites, they get saved before it and restored after it */
+/* {name} gets replaced with the register of the operand called name, or if there isn't one, with the address of the variable called name */
+
+#[derive(PartialEq)]
+enum OperandKind {
+	In,
+	Out,
+	InOut
+}
+
+struct AsmOperand {
+	name: String,
+	kind: OperandKind,
+	/* the 64 bit register, or None if the compiler should pick one */
+	register: Option<&'static str>,
+	/* the expression for in, the variable for out and inout */
+	value: Expression
+}
+
+/* the registers that the compiler picks from for (reg) operands, rbx isn't here because expressions get evaluated in it */
+const OPERAND_REGISTERS: [&str; 9] = ["rax", "rcx", "rdx", "rsi", "rdi", "r8", "r9", "r10", "r11"];
+
+/* returns the 64 bit register that a register constraint like "rcx" refers to */
+fn asm_register(state: &State, register: &str) -> Result<&'static str, (String, i64)> {
+	match full_register(register) {
+		Some("rsp" | "rbp") => Err((format!("the asm! macro cannot use '{register}', the compiler needs it for the stack frame"), state.line)),
+		Some(x) => Ok(x),
+		None => Err((format!("'{register}' is not a general purpose register in call to asm! macro"), state.line))
+	}
+}
+
+/* parses an operand, like 'a = in(reg) x' */
+fn parse_operand(state: &State, arg: &Expression) -> Result<AsmOperand, (String, i64)> {
+	let (name, kind, constraint, value) = match arg.as_slice() {
+		[Identifier(name), Operator(Equal), Identifier(kind), Operator(LeftParen), constraint, Operator(RightParen), value @ ..] => (name, kind, constraint, value),
+		_ => return Err(("expected an operand like 'name = in(reg) value' or clobber(\"register\") in call to asm! macro".to_owned(), state.line))
+	};
+
+	let kind = match kind.as_str() {
+		"in" => OperandKind::In,
+		"out" => OperandKind::Out,
+		"inout" => OperandKind::InOut,
+		_ => return Err((format!("expected 'in', 'out' or 'inout' for operand '{name}' in call to asm! macro, but got '{kind}'"), state.line))
+	};
+
+	let register = match constraint {
+		Identifier(x) if x == "reg" => None,
+		StringLiteral(x) => Some(asm_register(state, x)?),
+		_ => return Err((format!("expected 'reg' or a register like \"rax\" as the register of operand '{name}' in call to asm! macro"), state.line))
+	};
+
+	if (value.is_empty()) {
+		return Err((format!("expected a value for operand '{name}' in call to asm! macro"), state.line));
+	}
+
+	/* outputs get stored in a variable, so they can't be any other expression */
+	if (kind != OperandKind::In) {
+		match value {
+			[Identifier(x)] if state.function.local_variables.contains_key(x) => (),
+			[Identifier(x)] => return Err((format!("undeclared variable '{x}' for operand '{name}' in call to asm! macro"), state.line)),
+			_ => return Err((format!("the value of output operand '{name}' in call to asm! macro must be a variable"), state.line))
+		}
+	}
+
+	Ok(AsmOperand { name: name.clone(), kind, register, value: value.to_vec() })
+}
+
+/* turns one line of an asm! template into nasm */
+/* 'operands' are the names of the operands and the registers they ended up in */
+fn parse_asm(state: &State, input: &str, operands: &[(String, &str)]) -> Result<String, (String, i64)> {
 	let tokens = match lexer::lex(input) {
 		Ok(x) => x,
-		Err(err) => return Err((err, state.line))
+		Err((err, ..)) => return Err((err, state.line))
 	};
 
 	let mut iter = tokens.iter();
@@ -71,24 +825,35 @@ fn parse_asm(state: &State, input: &str) -> Result<String, (String, i64)> {
 					Some(Identifier(x)) => x,
 					_ => return Err((String::from("expected identifier after '{' in asm! macro call"), state.line))
 				};
-				
-				let variable = match state.function.local_variables.get(identifier) {
-					Some(x) => x,
-					None => return Err((format!("undeclared variable '{identifier}' in asm! macro call"), state.line))
-				};
 
 				match iter.next() {
 					Some(Operator(RightCurly)) => (),
 					_ => return Err((format!("expected '}}' after identifier '{identifier}' in asm! macro call"), state.line))
 				}
 
+				if let Some((_, register)) = operands.iter().find(|(name, _)| name == identifier) {
+					output.push_str(&format!("{register} "));
+					continue;
+				}
+				
+				let variable = match state.function.local_variables.get(identifier) {
+					Some(x) => x,
+					None => return Err((format!("undeclared variable or operand '{identifier}' in asm! macro call"), state.line))
+				};
+
 				output.push_str(&format!("{} ", variable.addr));
 			}
 
 			Keyword(x) => output.push_str(&format!("{:?}", x).to_lowercase()),
 			
 			Identifier(x) | Numerical(x) => output.push_str(&format!("{x} ")),
-			Operator(Comma) => { output.pop(); output.push_str(", ") },
+			Operator(Comma) => {
+				/* identifiers get a space after them, which shouldn't go before the comma */
+				if (output.ends_with(' ')) {
+					output.pop();
+				}
+				output.push_str(", ")
+			},
 			Operator(x) => output.push_str(&x.to_string()),
 
 			StringLiteral(_) => return Err((String::from("string literals are not allowed in the asm! macro"), state.line)),
@@ -102,42 +867,222 @@ fn parse_asm(state: &State, input: &str) -> Result<String, (String, i64)> {
 }
 
 fn asm(state: &mut State, args: &[Expression]) -> Result<Option<String>, (String, i64)> {
-	if (args.len() != 1) {
-		return Err((format!("asm! macro accepts 1 argument, not {}", args.len()), state.line))
+	/* the template lines come first */
+	let mut templates = Vec::new();
+	let mut iter = args.iter().peekable();
+	while let Some([StringLiteral(x)]) = iter.peek().map(|x| x.as_slice()) {
+		templates.push(x.clone());
+		iter.next();
 	}
 
-	let instruction = match &args[0][0] {
-		StringLiteral(ref x) => x,
-		err => return Err((format!("expected token type to be a string literal, not {err}"), state.line))
-	};
+	if (templates.is_empty()) {
+		return Err(("asm! macro expects at least one line of assembly as a string literal".to_owned(), state.line));
+	}
+
+	/* and then the operands and clobbers */
+	let mut operands: Vec<AsmOperand> = Vec::new();
+	let mut clobbers: Vec<&'static str> = Vec::new();
+
+	for i in iter {
+		if let [Identifier(x), Operator(LeftParen), registers @ .., Operator(RightParen)] = i.as_slice() {
+			if (x == "clobber") {
+				for register in registers.iter().filter(|x| !matches!(x, Operator(Comma))) {
+					let register = match register {
+						StringLiteral(x) => asm_register(state, x)?,
+						_ => return Err(("expected the registers in clobber to be string literals, like clobber(\"rcx\")".to_owned(), state.line))
+					};
+
+					if (!clobbers.contains(&register)) {
+						clobbers.push(register);
+					}
+				}
+
+				continue;
+			}
+		}
+
+		let operand = parse_operand(state, i)?;
+		if (operands.iter().any(|x| x.name == operand.name)) {
+			return Err((format!("operand '{}' is defined more than once in call to asm! macro", operand.name), state.line));
+		}
+
+		operands.push(operand);
+	}
+
+	/* figure out which register every operand goes in */
+	/* the registers that were asked for come first, and the compiler picks from the ones that are left for the rest */
+	let mut used: Vec<&'static str> = clobbers.clone();
+	for i in operands.iter().filter(|x| x.register.is_some()) {
+		/* unwrap will never fail, we just filtered for it */
+		let register = i.register.unwrap();
+
+		if (used.contains(&register)) {
+			return Err((format!("register '{register}' of operand '{}' is already used by another operand or clobbered in call to asm! macro", i.name), state.line));
+		}
+
+		used.push(register);
+	}
+
+	for i in operands.iter_mut().filter(|x| x.register.is_none()) {
+		let register = match OPERAND_REGISTERS.iter().find(|x| !used.contains(x)) {
+			Some(x) => *x,
+			None => return Err((format!("ran out of registers for operand '{}' in call to asm! macro", i.name), state.line))
+		};
+
+		i.register = Some(register);
+		used.push(register);
+	}
+
+	/* the types decide which part of the register gets used, like eax for an i32 */
+	let mut types = Vec::new();
+	for i in &operands {
+		let datatype = infer_type(state, &i.value)?;
+		if (datatype.is_tuple()) {
+			return Err((format!("operand '{}' in call to asm! macro is of type '{}', but only integers can be put in registers", i.name, datatype.string), state.line));
+		}
+
+		types.push(datatype);
+	}
+
+	/* evaluate all of the inputs before any of them are put in their registers, so that evaluating one doesn't overwrite another */
+	/* if the value of an input ends up in a register, it gets moved to the stack for now */
+	let mut inputs = Vec::new();
+	for (operand, datatype) in operands.iter().zip(&types) {
+		if (operand.kind == OperandKind::Out) {
+			continue;
+		}
+
+		let mut value = eval_expression(state, &operand.value, datatype)?;
+		if (is_register(&value)) {
+			let addr = reserve_stack(state, datatype);
+			state.textsect.push_str(&format!("\tmov {} {addr}, {value}\n", datatype.word));
+			value = addr;
+		}
+
+		inputs.push((operand, datatype, value));
+	}
+
+	/* save the clobbered registers */
+	let mut saved = Vec::new();
+	for i in &clobbers {
+		let addr = reserve_stack(state, &DataType::new("i64", state.line)?);
+		state.textsect.push_str(&format!("\tmov qword {addr}, {i}\n"));
+		saved.push((i, addr));
+	}
+
+	for (operand, datatype, value) in inputs {
+		/* unwrap will never fail, every operand got a register */
+		let register = sized_register(operand.register.unwrap(), &datatype.word);
+
+		if (value.starts_with('[')) {
+			state.textsect.push_str(&format!("\tmov {register}, {} {value}\n", datatype.word));
+		}
+		else {
+			state.textsect.push_str(&format!("\tmov {register}, {value}\n"));
+		}
+	}
+
+	/* the asm itself */
+	let names: Vec<(String, &str)> = operands.iter().zip(&types).map(|(operand, datatype)| {
+		(operand.name.clone(), sized_register(operand.register.unwrap(), &datatype.word))
+	}).collect();
+
+	for i in &templates {
+		let parsed = parse_asm(state, i, &names)?;
+		state.textsect.push_str(&format!("\t{parsed}\n"));
+	}
 
-	/* state doesnt get mutated here, just read  */
-	let parsed = parse_asm(state, instruction)?;
-	state.textsect.push_str(&format!("\t{parsed}\n\n"));
+	/* store the outputs in their variables */
+	for ((operand, datatype), (_, register)) in operands.iter().zip(&types).zip(&names) {
+		if (operand.kind == OperandKind::In) {
+			continue;
+		}
+
+		/* unwrap will never fail, parse_operand checks that the value is a variable */
+		let variable = match operand.value.as_slice() {
+			[Identifier(x)] => state.function.local_variables.get(x).unwrap(),
+			_ => unreachable!()
+		};
+
+		state.textsect.push_str(&format!("\tmov {} {}, {register}\n", datatype.word, variable.addr));
+	}
+
+	/* and restore the clobbered registers */
+	for (register, addr) in saved {
+		state.textsect.push_str(&format!("\tmov {register}, qword {addr}\n"));
+	}
+
+	state.textsect.push('\n');
 
 	Ok(None)
 }
 
-/* ------------------ */
-/*      syscall!      */
-/* ------------------ */
-fn syscall(state: &mut State, args: &[Expression]) -> Result<Option<String>, (String, i64)> {
-	for (i, v) in args.iter().enumerate() {
-		let v = eval_expression(state, v, &DataType::new("i64", state.line)?)?;
+/* --------------------- */
+/*      global_asm!      */
+/* --------------------- */
+/* global_asm!("line", "more lines", section(".text.boot")) writes assembly outside of any function */
+/* the lines are written as they are, without any substitutions, the section is .text if none is given */
+/* nasm only knows the flags of sections with standard names, others need them written out, like section(".boot progbits alloc exec") */
+fn global_asm(state: &mut State, args: &[Expression]) -> Result<Option<String>, (String, i64)> {
+	if (!state.function.name.is_empty()) {
+		return Err(("global_asm! can only be used outside of functions, use asm! instead".to_owned(), state.line));
+	}
 
-		match i {
-			0 => state.textsect.push_str(&format!("\tmov rax, {v}\n")),
-			1 => state.textsect.push_str(&format!("\tmov rdi, {v}\n")),
-			2 => state.textsect.push_str(&format!("\tmov rsi, {v}\n")),
-			3 => state.textsect.push_str(&format!("\tmov rdx, {v}\n")),
-			4 => state.textsect.push_str(&format!("\tmov r10, {v}\n")),
-			5 => state.textsect.push_str(&format!("\tmov r8, {v}\n")),
-			6 => state.textsect.push_str(&format!("\tmov r9, {v}\n")),
-			_ => return Err((String::from("syscall! does not take more than 7 arguments"), state.line))
+	let mut lines = Vec::new();
+	let mut section = None;
+
+	for i in args {
+		match i.as_slice() {
+			[StringLiteral(x)] => lines.push(x),
+			[Identifier(x), Operator(LeftParen), StringLiteral(name), Operator(RightParen)] if x == "section" => {
+				if (section.is_some()) {
+					return Err(("global_asm! can only have one section".to_owned(), state.line));
+				}
+
+				section = Some(name);
+			}
+			_ => return Err(("expected lines of assembly as string literals or section(\"name\") in call to global_asm! macro".to_owned(), state.line))
 		}
 	}
-	
-	state.textsect.push_str("\tsyscall\n\n");
 
-	Ok(Some(String::from("rax")))
+	if (lines.is_empty()) {
+		return Err(("global_asm! macro expects at least one line of assembly as a string literal".to_owned(), state.line));
+	}
+
+	if let Some(x) = section {
+		state.textsect.push_str(&format!("section {x}\n"));
+	}
+
+	for i in lines {
+		state.textsect.push_str(&format!("{i}\n"));
+	}
+
+	/* go back to .text, which is where everything else is */
+	if (section.is_some()) {
+		state.textsect.push_str("section .text\n");
+	}
+	state.textsect.push('\n');
+
+	Ok(None)
+}
+
+/* ------------------ */
+/*     errno_str!     */
+/* ------------------ */
+/* the message for an errno code, as a str, the code can be negative like the ones that syscalls return */
+fn errno_str(state: &mut State, args: &[Expression]) -> Result<Option<String>, (String, i64)> {
+	let code = match args {
+		[x] => x,
+		_ => return Err((format!("errno_str! macro accepts 1 argument, not {}", args.len()), state.line))
+	};
+
+	let value = eval_expression(state, code, &DataType::new("i64", state.line)?)?;
+	state.textsect.push_str(&format!("\tmov rdi, {value}\n"));
+	call_routine(state, "catlang_errno_str");
+
+	let addr = reserve_stack(state, &DataType::new("str", state.line)?);
+	state.textsect.push_str(&format!("\tmov qword {addr}, rax\n"));
+	state.textsect.push_str(&format!("\tmov qword {}, rdx\n", member_address(&addr, 8)));
+
+	Ok(Some(addr))
 }
\ No newline at end of file
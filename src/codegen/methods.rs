@@ -0,0 +1,200 @@
+use super::*;
+
+/* methods are defined in impl blocks, like this: */
+/*
+	type Vec3 = (i64, i64, i64)
+
+	impl Vec3 {
+		fn new(x: i64, y: i64, z: i64) -> Self {
+			return (x, y, z)
+		}
+
+		fn sum(self) -> i64 {
+			let (x, y, z) = self
+			return x + y + z
+		}
+	}
+*/
+/* every function in an impl block becomes a regular function named after the type, so these are Vec3::new and Vec3::sum */
+/* Self stands for the type of the impl block, and a paramater called just 'self' is short for 'self: Self' */
+/* a method call like v.sum() gets turned into Vec3::sum(v) before it's evaluated, so the receiver is just the first argument (and it gets passed by value) */
+
+#[derive(Clone)]
+pub struct Method {
+	/* the name of the function that the method turned into, like Vec3::sum */
+	pub function: String,
+	/* methods that don't take self can only be called as Vec3::new(...) */
+	pub takes_self: bool
+}
+
+/* generates the functions in an impl block, and adds them to the methods of the type */
+/* for 'impl Trait for Type' they also have to match the methods of the trait (see traits.rs) */
+pub fn define_impl(state: &mut State, name: &str, trait_name: Option<&str>, body: &[AstType]) -> Result<(), (String, i64)> {
+	if (!state.function.name.is_empty()) {
+		return Err((format!("cannot have 'impl {name}' inside of a function, impl blocks have to be at the top level of a file"), state.line));
+	}
+
+	if let Some(x) = trait_name {
+		check_impl(state, x, name, body)?;
+	}
+
+	/* methods get looked up through the type of the receiver, and for aliases that's the type they stand for */
+	let datatype = resolve_type(state, name)?;
+	let bindings = HashMap::from([("Self".to_owned(), name.to_owned())]);
+
+	let mut functions: Vec<AstType> = Vec::new();
+	for i in body {
+		let (method, public, attributes, type_params, (arg_names, arg_types), return_type, body) = match i {
+			FunctionDefinition(method, public, attributes, type_params, args, return_type, body) => (method, public, attributes, type_params, args, return_type, body),
+			AstType::Newline => {
+				functions.push(AstType::Newline);
+				continue;
+			}
+			_ => return Err((format!("only functions can be defined inside of 'impl {name}'"), state.line))
+		};
+
+		let key = (datatype.string.clone(), method.clone());
+		if (state.methods.contains_key(&key)) {
+			return Err((format!("method '{method}' is already defined for type '{}'", datatype.string), state.line));
+		}
+
+		let function = format!("{}::{method}", mangle_type(name));
+		if (state.functions.contains_key(&function)) {
+			return Err((format!("cannot define method '{method}' for type '{name}', there already is a function called {function}"), state.line));
+		}
+
+		state.methods.insert(key, Method { function: function.clone(), takes_self: arg_names.first().is_some_and(|x| x == "self") });
+
+		functions.push(FunctionDefinition(
+			function,
+			*public,
+			attributes.clone(),
+			type_params.clone(),
+			(arg_names.clone(), arg_types.iter().map(|x| substitute_type(x, &bindings)).collect()),
+			return_type.as_ref().map(|x| substitute_type(x, &bindings)),
+			substitute_ast(body, &bindings)
+		));
+	}
+
+	generate(state, &functions)?;
+
+	match trait_name {
+		Some(x) => add_impl(state, x, name),
+		None => Ok(())
+	}
+}
+
+/* finds the method called 'name' of a type */
+fn find_method(state: &State, datatype: &DataType, name: &str) -> Result<Method, (String, i64)> {
+	let method = match state.methods.get(&(datatype.string.clone(), name.to_owned())) {
+		Some(x) => x.clone(),
+		None => return Err((format!("type '{}' has no method called '{name}'", datatype.string), state.line))
+	};
+
+	if (!method.takes_self) {
+		return Err((format!("'{}' does not take self, so it has to be called as {}(...)", method.function, method.function), state.line));
+	}
+
+	Ok(method)
+}
+
+/* returns where the value that a method gets called on starts, like the 'v' in 'x + v.len()' or the 'f(x)' in 'f(x).len()' */
+/* this is also where the Result before a ? starts (see results.rs) */
+pub fn receiver_start(expr: &[Token]) -> usize {
+	/* 'f(x)?.len()' */
+	if let Some(Operator(Question)) = expr.last() {
+		return receiver_start(&expr[..expr.len()-1]);
+	}
+
+	let mut i = expr.len();
+
+	if let Some(Operator(RightParen)) = expr.last() {
+		let mut depth = 0;
+
+		while (i > 0) {
+			i -= 1;
+			match expr[i] {
+				Operator(RightParen) => depth += 1,
+				Operator(LeftParen) => depth -= 1,
+				_ => ()
+			}
+
+			if (depth == 0) {
+				break;
+			}
+		}
+
+		/* function calls, the name of the function comes before the parentheses */
+		if (i > 0 && matches!(expr[i-1], Identifier(_))) {
+			i -= 1;
+		}
+
+		return i;
+	}
+
+	i.saturating_sub(1)
+}
+
+/* turns every method call in an expression into a call to the function that the method turned into */
+/* so 'v.scale(2).sum()' becomes 'Vec3::sum(Vec3::scale(v, 2))' */
+pub fn lower_method_calls(state: &mut State, expr: &Expression) -> Result<Expression, (String, i64)> {
+	if (!expr.windows(3).any(|x| matches!(x, [Operator(Dot), Identifier(_), Operator(LeftParen)]))) {
+		return Ok(expr.clone());
+	}
+
+	let mut output: Expression = Vec::new();
+	let mut iter = expr.iter();
+
+	while let Some(token) = iter.next() {
+		let name = match (token, iter.clone().next(), iter.clone().nth(1)) {
+			(Operator(Dot), Some(Identifier(x)), Some(Operator(LeftParen))) => x.clone(),
+			_ => {
+				output.push(token.clone());
+				continue;
+			}
+		};
+
+		iter.next(); /* strip the name */
+		iter.next(); /* strip ( */
+		let args = process_function_parameters(&mut iter);
+
+		let receiver = output.split_off(receiver_start(&output));
+		if (receiver.is_empty()) {
+			return Err((format!("expected a value to call method '{name}' on before '.{name}()'"), state.line));
+		}
+
+		let receiver_type = infer_type(state, &receiver)?;
+		let method = find_method(state, &receiver_type, &name)?;
+
+		output.push(Identifier(method.function));
+		output.push(Operator(LeftParen));
+		output.extend(receiver);
+		for i in args {
+			output.push(Operator(Comma));
+			output.extend(i);
+		}
+		output.push(Operator(RightParen));
+	}
+
+	Ok(output)
+}
+
+/* a method call on its own line, like 'v.print()' */
+pub fn call_method(state: &mut State, expr: &Expression) -> Result<(), (String, i64)> {
+	let lowered = lower_method_calls(state, expr)?;
+	let mut iter = lowered.iter();
+
+	match (iter.next(), iter.next()) {
+		(Some(Identifier(name)), Some(Operator(LeftParen))) => {
+			let args = process_function_parameters(&mut iter);
+
+			if let Some(x) = iter.next() {
+				return Err((format!("unexpected {x} after method call, the return value of a method can only be used in an expression"), state.line));
+			}
+
+			call_function(state, name, &args)?;
+			Ok(())
+		}
+		_ => Err(("expected a method call, like 'v.print()'".to_owned(), state.line))
+	}
+}
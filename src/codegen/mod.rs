@@ -1,18 +1,43 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 mod registers;
 use registers::*;
 
 mod macros;
 mod expressions;
+mod aggregates;
+mod generics;
+mod inlining;
+mod runtime;
+mod methods;
+mod traits;
+mod operators;
+mod results;
+mod conditionals;
+mod syscalls;
 
 use expressions::*;
+use aggregates::*;
+use generics::*;
+use inlining::*;
+use runtime::*;
+use methods::*;
+use traits::*;
+use operators::*;
+use results::*;
+use conditionals::*;
+use syscalls::*;
+
+pub use runtime::emit_runtime;
+pub use operators::define_operator_traits;
+pub use macros::is_builtin_macro;
 
 use crate::parser::AstType::{self, *};
 use crate::lexer::Token::{self, *};
 use crate::lexer::Operator::*;
 
-use crate::parser::{process_function_parameters, Expression, ComparisonOperator};
+use crate::options::PanicStrategy;
+use crate::parser::{process_function_parameters, Expression, BlockStatement, ComparisonOperator, Attribute};
 
 /* ------------------------------ */
 /*           structures           */
@@ -20,9 +45,10 @@ use crate::parser::{process_function_parameters, Expression, ComparisonOperator}
 
 #[derive(Clone, PartialEq)]
 pub struct DataType {
-	string: String, /* i8, i16, i32, i64 */
+	string: String, /* i8, i16, i32, i64, (i64, i32), str, [i32] */
 	word: WordType,
-	byte_size: i32
+	byte_size: i32,
+	members: Vec<DataType> /* the element types of a tuple, (i64, i64) for slices, empty for everything else */
 }
 
 #[derive(Clone)]
@@ -34,7 +60,16 @@ struct Variable {
 #[derive(Clone)]
 struct Function {
 	arg_types: Vec<String>, /* types of paramaters, but not the names of the paramaters */
-	return_type: Option<DataType>
+	return_type: Option<DataType>,
+	/* private functions can only be called from the file they're defined in */
+	public: bool,
+	file: String,
+	attributes: Vec<Attribute>,
+	/* the body of the function, if it's #[inline] */
+	inline: Option<InlineBody>,
+	/* the methods of traits that get called on a dyn, like Writer::write, aren't real functions */
+	/* they call whatever is at this index in the vtable of the dyn instead (see traits.rs) */
+	vtable_slot: Option<usize>
 }
 
 /* this contains all of the state of the current function we're working with */
@@ -46,11 +81,20 @@ struct CurrentFunctionState {
 
 	local_variables: HashMap<String, Variable>,
 	return_type: Option<DataType>,
+	/* where the hidden pointer to the memory that a big tuple gets returned in is stored at */
+	return_pointer: Option<String>,
 	stacksize: i32,
 	stackspace: i32,
 
 	calls_funcs: bool,
 	returns: bool,
+	/* #[noreturn] functions can't have return statements */
+	noreturn: bool,
+
+	/* when we're generating the body of an inline function, return statements jump to this label instead of the epilogue */
+	inline_return: Option<String>,
+	/* the inline functions we're currently in the middle of inlining, so that they don't get inlined into themselves */
+	inlining: Vec<String>,
 }
 
 /* contains all of the state that this module needs to preserve */
@@ -60,14 +104,46 @@ struct CurrentFunctionState {
 #[derive(Default, Clone)]
 pub struct State {
 	line: i64,
+	/* the file that is currently being compiled, which changes when we get to the code of an imported module */
+	pub file: String,
 
 	pub datasect: String,
+	/* read only data, like files embedded with include_bytes! */
+	pub rodata: String,
 	pub textsect: String,
 
+	/* what panic! does, set with --panic */
+	pub panic: PanicStrategy,
+	/* disables dbg!, set with --release */
+	pub release: bool,
+
 	functions: HashMap<String, Function>,
 	function: CurrentFunctionState,
 
+	generics: HashMap<String, GenericFunction>,
+
+	/* type aliases, the name of the alias -> the type it stands for (see resolve_type) */
+	types: HashMap<String, DataType>,
+	/* (the type, the name of the method) -> the function it turned into (see methods.rs) */
+	methods: HashMap<(String, String), Method>,
+	/* the name of a trait -> its methods, and every (trait, type) that has an impl of that trait (see traits.rs) */
+	traits: HashMap<String, Vec<TraitMethod>>,
+	impls: HashSet<(String, String)>,
+
+	/* every function that got called somewhere, and every private function that got defined (name, file, line) */
+	/* used for warning about private functions that are never called */
+	called: HashSet<String>,
+	private_functions: Vec<(String, String, i64)>,
+	/* warnings that aren't errors, (message, file, line) */
+	warnings: Vec<(String, String, i64)>,
+	/* the code of instances of generic functions, which gets appended after the function that instantiated them */
+	instances: String,
+	/* the runtime routines that got called somewhere (see runtime.rs) */
+	runtime: HashSet<&'static str>,
+
 	labels: i64,
+	/* the amount of labels in rodata */
+	rodata_labels: i64,
 }
 
 /* ------------------------------- */
@@ -75,16 +151,299 @@ pub struct State {
 /* ------------------------------- */
 
 impl DataType {
+	/* for the builtin types, types written by the user can contain aliases so they have to go through resolve_type */
 	fn new(input: &str, line: i64) -> Result<Self, (String, i64)> {
+		Self::with_aliases(input, &HashMap::new(), line)
+	}
+
+	fn with_aliases(input: &str, aliases: &HashMap<String, DataType>, line: i64) -> Result<Self, (String, i64)> {
 		Ok(match input {
-			"i8" => Self { string: input.to_owned(), word: WordType::Byte, byte_size: 1 },
-			"i16" => Self { string: input.to_owned(), word: WordType::Word, byte_size: 2 },
-			"i32" => Self { string: input.to_owned(), word: WordType::DoubleWord, byte_size: 4 },
-			"i64" => Self { string: input.to_owned(), word: WordType::QuadWord, byte_size: 8 },
+			"i8" => Self { string: input.to_owned(), word: WordType::Byte, byte_size: 1, members: Vec::new() },
+			"i16" => Self { string: input.to_owned(), word: WordType::Word, byte_size: 2, members: Vec::new() },
+			"i32" => Self { string: input.to_owned(), word: WordType::DoubleWord, byte_size: 4, members: Vec::new() },
+			"i64" => Self { string: input.to_owned(), word: WordType::QuadWord, byte_size: 8, members: Vec::new() },
+
+			/* placeholders for type paramaters, which are used for type checking generic functions (see generics.rs) */
+			_ if input.starts_with('<') && input.ends_with('>') => Self { string: input.to_owned(), word: WordType::QuadWord, byte_size: 8, members: Vec::new() },
+
+			/* strings and slices are a pointer and a length, they're laid out (and passed around) just like (i64, i64) */
+			"str" => Self::slice(input),
+			_ if input.starts_with('[') && input.ends_with(']') => {
+				let element = DataType::with_aliases(&input[1..input.len()-1], aliases, line)?;
+				Self::slice(&format!("[{}]", element.string))
+			}
+
+			/* tuple types, like (i64, i32) */
+			_ if input.starts_with('(') && input.ends_with(')') => {
+				let mut members: Vec<DataType> = Vec::new();
+				for i in split_tuple_type(&input[1..input.len()-1]) {
+					members.push(DataType::with_aliases(i, aliases, line)?);
+				}
+
+				if (members.len() < 2) {
+					return Err((format!("'{input}' is not a valid type, tuples must have at least 2 elements"), line));
+				}
+
+				DataType::tuple(members)
+			}
+
+			/* results, like Result<i64, i32> */
+			_ if input.starts_with("Result<") && input.ends_with('>') => {
+				let mut members: Vec<DataType> = Vec::new();
+				for i in split_tuple_type(&input["Result<".len()..input.len()-1]) {
+					members.push(DataType::with_aliases(i, aliases, line)?);
+				}
+
+				match <[DataType; 2]>::try_from(members) {
+					Ok([ok, err]) => DataType::result(ok, err),
+					Err(_) => return Err((format!("'{input}' is not a valid type, a Result has to have 2 types, like Result<i64, i32>"), line))
+				}
+			}
+
+			_ if aliases.contains_key(input) => aliases[input].clone(),
 			
 			_ => return Err((format!("'{input}' is not a valid type"), line)) 
 		})
 	}
+
+	/* constructs a tuple type out of its element types, the elements are laid out in memory like the fields of a C struct */
+	fn tuple(members: Vec<DataType>) -> Self {
+		let string = format!("({})", members.iter().map(|x| x.string.as_str()).collect::<Vec<&str>>().join(", "));
+
+		let mut byte_size = 0;
+		for i in &members {
+			byte_size = align_to(byte_size, i.alignment()) + i.byte_size;
+		}
+
+		/* the size of a tuple is padded so that the next element of an array of tuples would also be aligned */
+		let alignment = members.iter().map(|x| x.alignment()).max().unwrap_or(1);
+
+		Self {
+			string,
+			/* tuples always live in memory and get moved around in pieces, so this is never used for them */
+			word: WordType::QuadWord,
+			byte_size: align_to(byte_size, alignment),
+			members
+		}
+	}
+
+	fn slice(string: &str) -> Self {
+		let i64 = Self { string: "i64".to_owned(), word: WordType::QuadWord, byte_size: 8, members: Vec::new() };
+
+		Self {
+			string: string.to_owned(),
+			word: WordType::QuadWord,
+			byte_size: 16,
+			members: vec![i64.clone(), i64]
+		}
+	}
+
+	/* a Result is laid out like the tuple (i64, T, E), the first element is 0 if it's Ok(T) and 1 if it's Err(E) */
+	/* only the element that goes with the first one ever gets written to */
+	fn result(ok: DataType, err: DataType) -> Self {
+		let i64 = Self { string: "i64".to_owned(), word: WordType::QuadWord, byte_size: 8, members: Vec::new() };
+
+		Self {
+			string: format!("Result<{}, {}>", ok.string, err.string),
+			..Self::tuple(vec![i64, ok, err])
+		}
+	}
+
+	/* returns the types of Ok and Err if this is a Result */
+	fn result_types(&self) -> Option<(&DataType, &DataType)> {
+		if (self.string.starts_with("Result<")) {
+			return Some((&self.members[1], &self.members[2]));
+		}
+
+		None
+	}
+
+	/* returns the type of the elements of a slice, or None if it isn't one, the elements of a str are bytes */
+	fn element_type(&self, state: &State) -> Result<Option<DataType>, (String, i64)> {
+		if (self.string == "str") {
+			return Ok(Some(DataType::new("i8", state.line)?));
+		}
+
+		if (self.string.starts_with('[')) {
+			return Ok(Some(resolve_type(state, &self.string[1..self.string.len()-1])?));
+		}
+
+		Ok(None)
+	}
+
+	/* tuples, strings and slices, which all live in memory and get moved around in pieces */
+	fn is_tuple(&self) -> bool {
+		!self.members.is_empty()
+	}
+
+	fn alignment(&self) -> i32 {
+		match self.members.iter().map(|x| x.alignment()).max() {
+			Some(x) => x,
+			None => self.byte_size
+		}
+	}
+
+	/* returns the offset of every element of a tuple from the start of the tuple */
+	fn member_offsets(&self) -> Vec<i32> {
+		let mut offsets = Vec::new();
+		let mut offset = 0;
+
+		for i in &self.members {
+			offset = align_to(offset, i.alignment());
+			offsets.push(offset);
+			offset += i.byte_size;
+		}
+
+		offsets
+	}
+}
+
+/* turns the name of a type into a DataType, with every alias in it replaced by the type it stands for */
+/* so with 'type Fd = i32', (Fd, i64) becomes (i32, i64) */
+fn resolve_type(state: &State, input: &str) -> Result<DataType, (String, i64)> {
+	DataType::with_aliases(input, &state.types, state.line)
+}
+
+/* defines a type alias, the type it stands for gets resolved right away, so aliases can only refer to types that were defined before them */
+/* a newtype has the same layout as the type it stands for, but a different name, so the two can't be used in place of each other */
+fn define_type_alias(state: &mut State, name: &str, aliased: &str, newtype: bool) -> Result<(), (String, i64)> {
+	if (!state.function.name.is_empty()) {
+		return Err((format!("cannot define type {name} inside of a function, types have to be defined at the top level of a file"), state.line));
+	}
+
+	if (DataType::new(name, state.line).is_ok()) {
+		return Err((format!("cannot define type {name}, there's already a builtin type with that name"), state.line));
+	}
+
+	if (state.types.contains_key(name)) {
+		return Err((format!("type {name} is already defined"), state.line));
+	}
+
+	if (state.traits.contains_key(name)) {
+		return Err((format!("cannot define type {name}, there's already a trait with that name"), state.line));
+	}
+
+	let mut datatype = match resolve_type(state, aliased) {
+		Ok(x) => x,
+		Err(_) if aliased == name => return Err((format!("type {name} cannot be defined as itself"), state.line)),
+		Err(err) => return Err(err)
+	};
+
+	if (newtype) {
+		/* slices and strings are found by their name, so they would stop being slices if they got a different one */
+		if (datatype.is_tuple()) {
+			return Err((format!("newtype {name} can only stand for an integer type, not '{}', use 'type' instead", datatype.string), state.line));
+		}
+
+		datatype.string = name.to_owned();
+	}
+
+	state.types.insert(name.to_owned(), datatype);
+
+	Ok(())
+}
+
+/* the entry points have to be visible to the linker even though they're never called, so they're always global */
+fn is_entry_point(name: &str) -> bool {
+	name == "main" || name == "_start"
+}
+
+/* errors if 'name' is a private function from a different file than the one we're in */
+fn check_visibility(state: &State, name: &str) -> Result<(), (String, i64)> {
+	let (public, file) = match (state.functions.get(name), state.generics.get(name)) {
+		(Some(x), _) => (x.public, &x.file),
+		(_, Some(x)) => (x.public, &x.file),
+		(None, None) => return Ok(())
+	};
+
+	if (!public && file != &state.file) {
+		return Err((format!("function '{name}' is private to {file}, make it pub if you want to call it from other files"), state.line));
+	}
+
+	Ok(())
+}
+
+/* returns every warning, including private functions that are never called, as (message, file, line) */
+pub fn warnings(state: &State) -> Vec<(String, String, i64)> {
+	let mut warnings = state.warnings.clone();
+
+	for (name, file, line) in &state.private_functions {
+		if (!state.called.contains(name)) {
+			warnings.push((format!("function '{name}' is private and never called"), file.clone(), *line));
+		}
+	}
+
+	warnings
+}
+
+fn is_noreturn(state: &State, name: &str) -> bool {
+	match (state.functions.get(name), state.generics.get(name)) {
+		(Some(x), _) => x.attributes.contains(&Attribute::NoReturn),
+		(_, Some(x)) => x.attributes.contains(&Attribute::NoReturn),
+		(None, None) => false
+	}
+}
+
+/* where a return statement should jump to */
+fn return_label(state: &State) -> String {
+	match &state.function.inline_return {
+		Some(x) => x.clone(),
+		None => format!(".ret_{}", symbol_name(state, &state.function.name))
+	}
+}
+
+/* returns a tuple from the current function, in rax and rdx or through memory if it's too big for that */
+fn return_tuple(state: &mut State, addr: &str, return_type: &DataType) {
+	match state.function.return_pointer.clone() {
+		/* the address of the memory we wrote the tuple to also has to be returned in rax */
+		Some(return_pointer) => {
+			state.textsect.push_str(&format!("\tmov rdi, qword {return_pointer}\n"));
+			copy_tuple(state, addr, "[rdi]", return_type);
+			state.textsect.push_str("\tmov rax, rdi\n");
+		}
+		None => load_return_registers(state, addr, return_type)
+	}
+
+	state.textsect.push_str(&format!("\tjmp {}\n", return_label(state)));
+	state.function.returns = true;
+}
+
+/* functions in modules are named like io::write, but nasm doesn't allow ':' in symbols so they get emitted as io.write */
+/* methods are named like Vec3::sum (see methods.rs), those get emitted as Vec3$sum so that they can't be the same symbol as sum in a module called Vec3 */
+fn symbol_name(state: &State, name: &str) -> String {
+	if let Some((type_name, method)) = name.rsplit_once("::") {
+		if (state.methods.values().any(|x| x.function == name)) {
+			return format!("{}${method}", type_name.replace("::", "."));
+		}
+	}
+
+	name.replace("::", ".")
+}
+
+fn align_to(value: i32, alignment: i32) -> i32 {
+	(value + alignment - 1) / alignment * alignment
+}
+
+/* splits the inside of a tuple type like "i64, (i32, i8)" into ["i64", "(i32, i8)"] */
+fn split_tuple_type(input: &str) -> Vec<&str> {
+	let mut members = Vec::new();
+	let mut depth = 0;
+	let mut start = 0;
+
+	for (i, v) in input.char_indices() {
+		match v {
+			'(' | '[' | '<' => depth += 1,
+			')' | ']' | '>' => depth -= 1,
+			',' if depth == 0 => {
+				members.push(input[start..i].trim());
+				start = i+1;
+			}
+			_ => ()
+		}
+	}
+	members.push(input[start..].trim());
+
+	members
 }
 
 impl Default for CurrentFunctionState {
@@ -93,6 +452,7 @@ impl Default for CurrentFunctionState {
 			name: String::new(),
 			local_variables: HashMap::new(),
 			return_type: None,
+			return_pointer: None,
 			
 			/* stacksize needs to start at 8 because whenever we push rbx, [rbp-8] becomes the location of rbx */
 			/* this is bad because whenever we make a variable we will start at [rbp-4] or [rbp-8] */
@@ -103,6 +463,10 @@ impl Default for CurrentFunctionState {
 
 			calls_funcs: false,
 			returns: false,
+			noreturn: false,
+
+			inline_return: None,
+			inlining: Vec::new(),
 		}
 	}
 }
@@ -119,23 +483,215 @@ fn resolve_string_literal(datasect: &mut String, literal: &str) -> String {
 	/* mutating a static mut is unsafe because it can cause data races with multithreading */
 	/* but because this program is singlethreaded (for now) this is perfectly safe */
 	unsafe {
-		datasect.push_str(&format!("\tL{LITERALS_AMOUNT}: db `{literal}`, 0\n"));
+		let literal_index = LITERALS_AMOUNT;
 		LITERALS_AMOUNT += 1;
 
-		format!("L{}", LITERALS_AMOUNT - 1)
+		datasect.push_str(&format!("\tL{literal_index}: db {}, 0\n", data_string(literal)));
+		format!("L{literal_index}")
 	}
 }
 
-/* adds a variable to the local_variables hashmap */
-fn add_variable(state: &mut State, name: &str, vartype: &DataType, initval: Option<&str>) -> Result<(), (String, i64)> {
-	state.function.stacksize += vartype.byte_size;
+/* writes a string literal the way nasm wants it in a db */
+/* the ascii parts go in backquotes, so that nasm handles the escape sequences in them */
+/* but characters that take more than one byte in utf-8 get written as their bytes, so they end up in the binary exactly like they are in the source */
+fn data_string(literal: &str) -> String {
+	let mut parts: Vec<String> = Vec::new();
+	let mut ascii = String::new();
+	let mut iter = literal.chars().peekable();
+
+	while let Some(i) = iter.next() {
+		if (i.is_ascii()) {
+			/* a backslash before a unicode character doesn't escape anything, so it stays a backslash */
+			/* (it has to be escaped itself, otherwise it would escape the closing backquote) */
+			if (i == '\\' && iter.peek().is_some_and(|x| !x.is_ascii())) {
+				ascii.push_str("\\\\");
+				continue;
+			}
+
+			ascii.push(i);
+
+			/* an escaped character always goes together with its backslash */
+			if (i == '\\') {
+				if let Some(x) = iter.next() {
+					ascii.push(x);
+				}
+			}
+			continue;
+		}
+
+		if (!ascii.is_empty()) {
+			parts.push(format!("`{ascii}`"));
+			ascii.clear();
+		}
+
+		let mut bytes = [0; 4];
+		parts.extend(i.encode_utf8(&mut bytes).bytes().map(|x| x.to_string()));
+	}
+
+	if (!ascii.is_empty() || parts.is_empty()) {
+		parts.push(format!("`{ascii}`"));
+	}
+
+	parts.join(", ")
+}
+
+/* turns the escape sequences in a string literal into the characters they stand for */
+/* string literals that go into .data get their escape sequences handled by nasm, but the ones that go into .rodata are written as bytes */
+fn unescape(input: &str) -> String {
+	let mut output = String::new();
+	let mut iter = input.chars();
+
+	while let Some(i) = iter.next() {
+		if (i != '\\') {
+			output.push(i);
+			continue;
+		}
+
+		match iter.next() {
+			Some('n') => output.push('\n'),
+			Some('t') => output.push('\t'),
+			Some('r') => output.push('\r'),
+			Some('0') => output.push('\0'),
+			/* same as in data_string, a backslash before a unicode character stays a backslash */
+			Some(x) if !x.is_ascii() => {
+				output.push('\\');
+				output.push(x);
+			}
+			Some(x) => output.push(x),
+			None => output.push('\\')
+		}
+	}
+
+	output
+}
+
+/* puts a string literal into .rodata and writes a str that points to it into a new stack slot, and returns the address of the slot */
+/* the length is known at compile time, the string still gets a null terminator so that .ptr can be passed to c functions */
+fn resolve_str_literal(state: &mut State, literal: &str) -> Result<String, (String, i64)> {
+	let text = unescape(literal);
+	let mut bytes = text.into_bytes();
+	let length = bytes.len();
+	bytes.push(0);
+
+	let label = resolve_rodata(state, &bytes);
+	let addr = reserve_stack(state, &DataType::new("str", state.line)?);
+
+	state.textsect.push_str(&format!("\tmov rax, {label}\n"));
+	state.textsect.push_str(&format!("\tmov qword {addr}, rax\n"));
+	state.textsect.push_str(&format!("\tmov qword {}, {length}\n", member_address(&addr, 8)));
+
+	Ok(addr)
+}
+
+/* generates the body of a #[naked] function, which can only have asm! without any operands in it */
+/* there's no stack frame, so there's nowhere for variables to be */
+fn generate_naked(state: &mut State, name: &str, body: &[AstType]) -> Result<(), (String, i64)> {
+	for i in body {
+		match i {
+			AstType::Newline => state.line += 1,
+			MacroCall(macro_name, args) if macro_name == "asm!" => {
+				if (!args.iter().all(|x| matches!(x.as_slice(), [StringLiteral(_)]))) {
+					return Err((format!("asm! in naked function '{name}' can't have operands, there's no stack frame to put them in"), state.line));
+				}
+
+				macros::call_macro(state, macro_name, args)?;
+			}
+			_ => return Err((format!("naked function '{name}' can only contain asm!, because it doesn't have a stack frame"), state.line))
+		}
+	}
+
+	Ok(())
+}
+
+/* compares the values of two expressions, so that one of the instructions from jump_instruction can be used after it */
+fn compare(state: &mut State, expr1: &Expression, expr2: &Expression) -> Result<(), (String, i64)> {
+	let expr_type = infer_type(state, expr1)?;
+
+	let value = eval_expression(state, expr1, &expr_type)?;
+	let value2 = eval_expression(state, expr2, &expr_type)?;
+
+	let accumulator = get_accumulator(&expr_type.word);
+
+	/* the size only goes before memory operands, 'mov rax, qword rbx' isn't valid */
+	if (value.starts_with('[')) {
+		state.textsect.push_str(&format!("\tmov {accumulator}, {} {value}\n", expr_type.word));
+	}
+	else if (value != accumulator) {
+		state.textsect.push_str(&format!("\tmov {accumulator}, {value}\n"));
+	}
 	
-	if (state.function.stacksize > state.function.stackspace + 8) {
+	state.textsect.push_str(&format!("\tcmp {accumulator}, {value2}\n"));
+
+	Ok(())
+}
+
+/* returns the instruction that jumps if the result of the comparison (see compare) is 'result' */
+fn jump_instruction(operator: &ComparisonOperator, result: bool) -> &'static str {
+	match (operator, result) {
+		(ComparisonOperator::Equal, true) => "je",
+		(ComparisonOperator::Equal, false) => "jne",
+		(ComparisonOperator::NotEqual, true) => "jne",
+		(ComparisonOperator::NotEqual, false) => "je",
+
+		(ComparisonOperator::GreaterThan, true) => "jg",
+		(ComparisonOperator::GreaterThan, false) => "jle",
+		(ComparisonOperator::LessThan, true) => "jl",
+		(ComparisonOperator::LessThan, false) => "jge",
+
+		(ComparisonOperator::GreaterThanEqual, true) => "jge",
+		(ComparisonOperator::GreaterThanEqual, false) => "jl",
+		(ComparisonOperator::LessThanEqual, true) => "jle",
+		(ComparisonOperator::LessThanEqual, false) => "jg",
+	}
+}
+
+/* inserts some bytes into the read only data section and returns the label for them (like RO0, RO1, etc...) */
+/* (not R0, R1, etc, because R8 to R15 would be registers) */
+fn resolve_rodata(state: &mut State, bytes: &[u8]) -> String {
+	let label = format!("RO{}", state.rodata_labels);
+	state.rodata_labels += 1;
+
+	state.rodata.push_str(&format!("\t{label}:\n"));
+
+	/* the bytes get written as numbers, so that we don't have to worry about escaping anything */
+	for i in bytes.chunks(16) {
+		let line: Vec<String> = i.iter().map(|x| x.to_string()).collect();
+		state.rodata.push_str(&format!("\t\tdb {}\n", line.join(", ")));
+	}
+
+	label
+}
+
+/* makes room on the stack for a value of the given type and returns its address */
+fn reserve_stack(state: &mut State, datatype: &DataType) -> String {
+	if (datatype.is_tuple()) {
+		/* tuples get padded to 8 bytes so that they can be moved around 8 bytes at a time (see aggregates.rs) */
+		/* the padding has to be below the tuple too, or writing its last 8 bytes would overwrite the variable before it */
+		state.function.stacksize = align_to(state.function.stacksize, 8) + align_to(datatype.byte_size, 8);
+	}
+	else {
+		state.function.stacksize += datatype.byte_size;
+	}
+	
+	while (state.function.stacksize > state.function.stackspace + 8) {
 		state.function.stackspace += 16
 	}
 
-	let addr = format!("[rbp-{}]", state.function.stacksize);
-	if let Some(initval) = initval {
+	format!("[rbp-{}]", state.function.stacksize)
+}
+
+/* adds a variable to the local_variables hashmap */
+fn add_variable(state: &mut State, name: &str, vartype: &DataType, initval: Option<&str>) -> Result<(), (String, i64)> {
+	let addr = reserve_stack(state, vartype);
+	if let Some(mut initval) = initval {
+		/* we can't mov a memory location to another memory location */
+		if (initval.starts_with('[')) {
+			let accumulator = get_accumulator(&vartype.word);
+			state.textsect.push_str(&format!("\tmov {accumulator}, {initval}\n"));
+
+			initval = accumulator;
+		}
+
 		state.textsect.push_str(&format!("\tmov {} {addr}, {initval}\n", vartype.word));
 	}
 
@@ -147,9 +703,23 @@ fn add_variable(state: &mut State, name: &str, vartype: &DataType, initval: Opti
 	Ok(())
 }
 
-fn call_function(state: &mut State, name: &str, args: &Vec<Expression>) -> Result<(), (String, i64)> {
+/* calls a function and returns its return type, and if it returns a tuple through memory, the address of that memory */
+fn call_function(state: &mut State, name: &str, args: &[Expression]) -> Result<(Option<DataType>, Option<String>), (String, i64)> {
+	check_visibility(state, name)?;
+
+	/* generic functions get called through the instance for the types of the arguments */
+	let generic_name = name;
+	let name = &resolve_function(state, name, args)?;
+
+	/* recursive calls don't count as the function being used */
+	if (name != &state.function.name) {
+		state.called.insert(generic_name.to_owned());
+		state.called.insert(name.clone());
+	}
+
 	let function = match state.functions.get(name).cloned() {
 		Some(x) => x,
+		None if name == "Ok" || name == "Err" => return Err((format!("{name}(...) makes a Result, so it can only be used where a Result is expected"), state.line)),
 		None => return Err((format!("undefined function '{name}'"), state.line))
 	};
 
@@ -164,123 +734,360 @@ fn call_function(state: &mut State, name: &str, args: &Vec<Expression>) -> Resul
 		}), state.line))
 	}
 
-	/* we set up a queue for passing arguments to our function, after we're done evaluating all of our expressions we */
-	/* insert the 2nd value of the tuple (the expression evaluation) to the first value of the tuple (the register) */
+	if (should_inline(state, name, &function)) {
+		return inline_call(state, name, &function, args);
+	}
+
+	let mut arg_types: Vec<DataType> = Vec::new();
+	for i in &function.arg_types {
+		arg_types.push(resolve_type(state, i)?);
+	}
+
+	/* tuples that are bigger than 16 bytes get returned through memory, we make room for them in our stack frame */
+	/* and pass a pointer to that memory in rdi, which the function we're calling will then write the tuple to */
+	let return_buffer = match &function.return_type {
+		Some(x) if classify(x) == PassingClass::Memory => Some(reserve_stack(state, x)),
+		_ => None
+	};
+
+	/* we evaluate all of the arguments before we pass any of them to their registers */
+	/* the reason for that is that an expression evaluation can also call other functions, like in an expression like this [sum(100, sum(50, 50))] */
+	/* so if the evaluation of an argument ends up in a register, we move it to the stack so that evaluating the next argument doesn't overwrite it */
+	let mut values: Vec<String> = Vec::new();
+	for (i, v) in args.iter().enumerate() {
+		let argtype = &arg_types[i];
+
+		if (argtype.is_tuple()) {
+			values.push(eval_tuple(state, v, argtype)?);
+			continue;
+		}
+
+		let expr_evaluation = eval_expression(state, v, argtype)?;
+
+		if (is_register(&expr_evaluation)) {
+			let addr = reserve_stack(state, argtype);
+			state.textsect.push_str(&format!("\tmov {} {addr}, {expr_evaluation}\n", argtype.word));
+
+			values.push(addr);
+		}
+		else {
+			values.push(expr_evaluation);
+		}
+	}
+
+	/* only the value in a dyn gets passed to the method, as its self, the vtable is just for finding the method */
+	if (function.vtable_slot.is_some()) {
+		arg_types[0] = DataType::new("i64", state.line)?;
+	}
 
-	/* the reason why we have this queue and why we dont just push the expr onto the register in the loop below, is that an expression evaluation can also call other functions */
-	/* like in an expression like this [sum(100, sum(50, 50))] */
-	/* without this queue passing all of the arguments to their registers would get totally messed up */
-	let mut args_queue: Vec<(&'static str, String)> = Vec::new();
-	/* this is for when the function we're calling has more than 6 arguments, and we need to push shit */
+	let locations = argument_locations(&arg_types, return_buffer.is_some());
+
+	/* this is for the arguments that dont fit in registers, which we need to push onto the stack */
 	let mut stack_offset = 0;
+	for i in 0..args.len() {
+		if (locations[i].is_none()) {
+			stack_offset += align_to(arg_types[i].byte_size, 8);
+		}
+	}
 
-	/* insert arguments to the queue */
-	for (i, v) in args.iter().enumerate().rev() {
-		let expr_evaluation = eval_expression(state, v, &DataType::new(&function.arg_types[i], state.line)?)?;
-		let argtype = DataType::new(&function.arg_types[i], state.line)?;
+	/* the stack has to be 16 byte aligned when calling a function */
+	if (stack_offset % 16 != 0) {
+		state.textsect.push_str("\tsub rsp, 8\n");
+		stack_offset += 8;
+	}
 
-		if (i >= 6) {
-			stack_offset += 8;
+	/* arguments on the stack get pushed from right to left */
+	for i in (0..args.len()).rev() {
+		if (locations[i].is_some()) {
+			continue;
+		}
 
-			let accumulator = get_accumulator(&argtype.word);
-			if (expr_evaluation != accumulator) {
-				state.textsect.push_str(&format!("\tmov {accumulator}, {expr_evaluation}\n"));
-			}
+		let argtype = &arg_types[i];
 
-			state.textsect.push_str(&format!("\tpush rax\n"));
+		if (argtype.is_tuple()) {
+			state.textsect.push_str(&format!("\tsub rsp, {}\n", align_to(argtype.byte_size, 8)));
+			copy_tuple(state, &values[i], "[rsp]", argtype);
 			continue;
 		}
 
-		let register = get_register(i, &argtype.word);
-		args_queue.push((register, expr_evaluation));
+		let accumulator = get_accumulator(&argtype.word);
+		if (values[i] != accumulator) {
+			state.textsect.push_str(&format!("\tmov {accumulator}, {}\n", values[i]));
+		}
+
+		state.textsect.push_str("\tpush rax\n");
 	}
 
 	/* now pass the arguments into their respective registers */
-	for (register, expr_eval) in args_queue {
-		state.textsect.push_str(&format!("\tmov {register}, {expr_eval}\n"));
+	for i in 0..args.len() {
+		let register = match locations[i] {
+			Some(x) => x,
+			None => continue
+		};
+
+		let argtype = &arg_types[i];
+
+		/* tuples get split up into 8 byte chunks, one for each register */
+		if (argtype.is_tuple()) {
+			state.textsect.push_str(&format!("\tmov {}, qword {}\n", get_register(register, &WordType::QuadWord), values[i]));
+
+			if (argtype.byte_size > 8) {
+				state.textsect.push_str(&format!("\tmov {}, qword {}\n", get_register(register+1, &WordType::QuadWord), member_address(&values[i], 8)));
+			}
+
+			continue;
+		}
+
+		state.textsect.push_str(&format!("\tmov {}, {}\n", get_register(register, &argtype.word), values[i]));
 	}
 
-	state.textsect.push_str(&format!("\tcall {name}\n\n"));
+	if let Some(x) = &return_buffer {
+		state.textsect.push_str(&format!("\tlea rdi, {x}\n"));
+	}
+
+	match function.vtable_slot {
+		Some(slot) => {
+			state.textsect.push_str(&format!("\tmov rax, qword {}\n", member_address(&values[0], 8)));
+			state.textsect.push_str(&format!("\tcall [rax+{}]\n\n", slot * 8));
+		}
+		None => state.textsect.push_str(&format!("\tcall {}\n\n", symbol_name(state, name)))
+	}
 	if (stack_offset > 0) {
 		state.textsect.push_str(&format!("\tadd rsp, {stack_offset}\n"));
 	}
 
 	state.function.calls_funcs = true;
 
-	Ok(())
+	Ok((function.return_type, return_buffer))
 }
 
 /* returns the state of the program on success, returns a string containing error information on failure */
 pub fn generate(state: &mut State, input: &[AstType]) -> Result<(), (String, i64)> {
 	let iter = input.iter();
 
+	/* set after a call to a #[noreturn] function, everything after it in the same block can never run */
+	let mut unreachable_after: Option<&String> = None;
+
 	for i in iter {
+		if let Some(name) = unreachable_after {
+			if (!matches!(i, AstType::Newline)) {
+				state.warnings.push((format!("unreachable code, '{name}' never returns"), state.file.clone(), state.line));
+				unreachable_after = None;
+			}
+		}
+
 		match i {
 			AstType::Newline => state.line += 1,
 			/* ---------------------------- */
+			/*           modules            */
+			/* ---------------------------- */
+			/* everything after this came from another file (see modules.rs) */
+			SourceFile(path) => {
+				state.file = path.clone();
+				state.line = 0;
+			},
+			TypeAlias(name, aliased, newtype) => define_type_alias(state, name, aliased, *newtype)?,
+			Impl(name, trait_name, body) => define_impl(state, name, trait_name.as_deref(), body)?,
+			Trait(name, body) => define_trait(state, name, body)?,
+			/* imports have already been loaded by the time we get here */
+			Import(path) => {
+				if (!state.function.name.is_empty()) {
+					return Err((format!("cannot import '{path}' inside of a function, imports have to be at the top level of a file"), state.line));
+				}
+			},
+			/* ---------------------------- */
 			/*     function definitions     */
 			/* ---------------------------- */
-			FunctionDefinition(name, args, return_type, body) => {
-				state.textsect.push_str(&format!("global {name}\n{name}:\n"));
-				state.textsect.push_str("\tpush rbp\n");
-				state.textsect.push_str("\tmov rbp, rsp\n\n");
+			FunctionDefinition(name, public, attributes, type_params, args, return_type, body) => {
+				if (!public && !is_entry_point(name)) {
+					state.private_functions.push((name.clone(), state.file.clone(), state.line));
+				}
 
-				let stack_subtraction_index = state.textsect.len() - 1;
+				let naked = attributes.contains(&Attribute::Naked);
+				if (naked && !type_params.is_empty()) {
+					return Err((format!("naked function '{name}' cannot be generic"), state.line));
+				}
+				if (naked && attributes.contains(&Attribute::Inline)) {
+					return Err((format!("function '{name}' cannot be both naked and inline, there would be nothing to inline"), state.line));
+				}
 
-				/* add arguments to the stack */
-				let mut stack_offset = 16;
-				for i in 0..args.0.len() {
-					let datatype = DataType::new(&args.1[i], state.line)?;
+				if (!type_params.is_empty()) {
+					define_generic(state, i)?;
+					continue;
+				}
 
-					if (i >= 6) {
-						let accumulator = get_accumulator(&datatype.word);
-						state.textsect.push_str(&format!("\tmov {accumulator}, {} [rbp+{stack_offset}]\n", datatype.word));
+				/* functions in a different section than .text switch to it and back after they're done */
+				let section = match (attributes.contains(&Attribute::Cold), attributes.iter().find_map(|x| match x {
+					Attribute::Section(x) => Some(x),
+					_ => None
+				})) {
+					(true, Some(_)) => return Err((format!("function '{name}' cannot be both cold and in a specific section"), state.line)),
+					(true, None) => Some(".text.unlikely"),
+					(false, section) => section.map(|x| x.as_str())
+				};
 
-						add_variable(state, &args.0[i], &datatype, Some(&accumulator))?;
+				if let Some(section) = section {
+					state.textsect.push_str(&format!("section {section} progbits alloc exec nowrite align=16\n"));
+				}
 
-						stack_offset += 8;
-						continue;
+				for i in attributes {
+					if let Attribute::Align(x) = i {
+						state.textsect.push_str(&format!("align {x}\n"));
 					}
+				}
 
-					let register = get_register(i, &datatype.word);
-					add_variable(state, &args.0[i], &datatype, Some(register))?;
+				/* private functions don't get exported, so they can't clash with functions from other object files or libc */
+				let symbol = symbol_name(state, name);
+				if (*public || is_entry_point(name)) {
+					state.textsect.push_str(&format!("global {symbol}\n"));
+				}
+				state.textsect.push_str(&format!("{symbol}:\n"));
+
+				/* naked functions are just their asm, the arguments are wherever the caller put them */
+				if (naked) {
+					state.functions.insert(name.to_string(), Function {
+						arg_types: args.1.to_vec(),
+						return_type: return_type.as_ref().map(|x| resolve_type(state, x)).transpose()?,
+						public: *public,
+						file: state.file.clone(),
+						attributes: attributes.to_vec(),
+						inline: None,
+						vtable_slot: None
+					});
+
+					generate_naked(state, name, body)?;
+
+					if (section.is_some()) {
+						state.textsect.push_str("section .text\n\n");
+					}
+					continue;
 				}
 
+				state.textsect.push_str("\tpush rbp\n");
+				state.textsect.push_str("\tmov rbp, rsp\n\n");
+
+				let stack_subtraction_index = state.textsect.len() - 1;
+
 				let return_type = match return_type {
-					Some(x) => Some(DataType::new(x, state.line)?),
+					Some(x) => Some(resolve_type(state, x)?),
 					None => None,
 				};
+
+				let mut arg_types: Vec<DataType> = Vec::new();
+				for i in &args.1 {
+					arg_types.push(resolve_type(state, i)?);
+				}
+
+				/* if we return a big tuple, the caller passes a pointer to where we should write it in rdi */
+				let returns_in_memory = matches!(&return_type, Some(x) if classify(x) == PassingClass::Memory);
+				if (returns_in_memory) {
+					let addr = reserve_stack(state, &DataType::new("i64", state.line)?);
+					state.textsect.push_str(&format!("\tmov qword {addr}, rdi\n"));
+
+					state.function.return_pointer = Some(addr);
+				}
+
+				/* add arguments to the stack */
+				let mut stack_offset = 16;
+				for (i, location) in argument_locations(&arg_types, returns_in_memory).into_iter().enumerate() {
+					let datatype = &arg_types[i];
+
+					match location {
+						/* tuples that were passed in registers */
+						Some(register) if datatype.is_tuple() => {
+							add_variable(state, &args.0[i], datatype, None)?;
+
+							/* unwrap will never fail, we just added the variable */
+							let addr = state.function.local_variables.get(&args.0[i]).unwrap().addr.clone();
+							state.textsect.push_str(&format!("\tmov qword {addr}, {}\n", get_register(register, &WordType::QuadWord)));
+
+							if (datatype.byte_size > 8) {
+								state.textsect.push_str(&format!("\tmov qword {}, {}\n", member_address(&addr, 8), get_register(register+1, &WordType::QuadWord)));
+							}
+						}
+						Some(register) => {
+							add_variable(state, &args.0[i], datatype, Some(get_register(register, &datatype.word)))?;
+						}
+						/* tuples that were passed on the stack */
+						None if datatype.is_tuple() => {
+							add_variable(state, &args.0[i], datatype, None)?;
+
+							/* unwrap will never fail, we just added the variable */
+							let addr = state.function.local_variables.get(&args.0[i]).unwrap().addr.clone();
+							copy_tuple(state, &format!("[rbp+{stack_offset}]"), &addr, datatype);
+
+							stack_offset += align_to(datatype.byte_size, 8);
+						}
+						None => {
+							let accumulator = get_accumulator(&datatype.word);
+							state.textsect.push_str(&format!("\tmov {accumulator}, {} [rbp+{stack_offset}]\n", datatype.word));
+
+							add_variable(state, &args.0[i], datatype, Some(accumulator))?;
+
+							stack_offset += 8;
+						}
+					}
+				}
 				
-				state.functions.insert(name.to_string(), Function { arg_types: args.1.to_vec(), return_type: return_type.clone() });
+				let inline = if (attributes.contains(&Attribute::Inline)) {
+					Some(InlineBody { arg_names: args.0.to_vec(), body: body.to_vec(), file: state.file.clone(), line: state.line })
+				}
+				else {
+					None
+				};
+
+				state.functions.insert(name.to_string(), Function {
+					arg_types: args.1.to_vec(),
+					return_type: return_type.clone(),
+					public: *public,
+					file: state.file.clone(),
+					attributes: attributes.to_vec(),
+					inline,
+					vtable_slot: None
+				});
 				state.function.return_type = return_type;
 				state.function.name = name.clone();
+				state.function.noreturn = attributes.contains(&Attribute::NoReturn);
 
 				/* parse and append the body of the funnction */
 				generate(state, body)?;
 
 				if (state.function.returns) {
-					state.textsect.push_str(&format!("\n.ret_{}:", state.function.name));
+					state.textsect.push_str(&format!("\n.ret_{}:", symbol_name(state, &state.function.name)));
 				}
 
 				/* we want to subtract the value of stackspace + 8 (+8 because of rbx) from rsp if we call other functions */
 				/* and if the aren't any local variables/arguments in the current function */
 				if (state.function.calls_funcs && state.function.stacksize != 0) {
-					state.textsect.push_str("\n\tpop rbx\n");
-
 					state.textsect.insert_str(stack_subtraction_index, &format!("\tsub rsp, {}\n", state.function.stackspace + 8));
-					state.textsect.insert_str(stack_subtraction_index, "\tpush rbx\n");
+				}
+				state.textsect.insert_str(stack_subtraction_index, "\tpush rbx\n");
 
+				/* a noreturn function doesn't need an epilogue, but if it somehow does get to the end we crash instead of running into whatever comes after it */
+				if (state.function.noreturn) {
+					state.textsect.push_str("\n\tud2\n\n");
+				}
+				else if (state.function.calls_funcs && state.function.stacksize != 0) {
+					/* rsp doesn't point to rbx anymore after we subtracted from it, so we can't just pop it */
+					state.textsect.push_str("\n\tmov rbx, qword [rbp-8]\n");
 					state.textsect.push_str("\tleave\n");
+					state.textsect.push_str("\tret\n\n");
 				}
 				else {
 					state.textsect.push_str("\n\tpop rbx\n");
-					state.textsect.insert_str(stack_subtraction_index, "\tpush rbx\n");
-					
 					state.textsect.push_str("\tpop rbp\n");
+					state.textsect.push_str("\tret\n\n");
 				}
-				
-				state.textsect.push_str("\tret\n\n");
+
+				if (section.is_some()) {
+					state.textsect.push_str("section .text\n\n");
+				}
+
 				state.function = CurrentFunctionState::default();
+
+				/* instances of generic functions that this function called */
+				let instances = std::mem::take(&mut state.instances);
+				state.textsect.push_str(&instances);
 			},
 			/* --------------------------- */
 			/*     function prototypes     */
@@ -289,27 +1096,52 @@ pub fn generate(state: &mut State, input: &[AstType]) -> Result<(), (String, i64
 				state.textsect.push_str(&format!("extern {name}\n"));
 
 				let return_type = match return_type {
-					Some(x) => Some(DataType::new(x, state.line)?),
+					Some(x) => Some(resolve_type(state, x)?),
 					None => None,
 				};
 
-				state.functions.insert(name.to_string(), Function { arg_types: args.to_vec(), return_type });
+				state.functions.insert(name.to_string(), Function { arg_types: args.to_vec(), return_type, public: true, file: state.file.clone(), attributes: Vec::new(), inline: None, vtable_slot: None });
 			}
 			/* -------------------------- */
 			/*      function calling      */
 			/* -------------------------- */
+			MethodCall(expr) => call_method(state, expr)?,
+			TryStatement(expr) => try_statement(state, expr)?,
 			FunctionCall(name, args) => {
 				call_function(state, name, args)?;
+
+				if (is_noreturn(state, name)) {
+					unreachable_after = Some(name);
+				}
 			},
 			/* ------------------------ */
 			/*    function returning    */
 			/* ------------------------ */
 			ReturnStatement(expr) => {
+				if (state.function.noreturn) {
+					return Err((format!("attempted to return from function '{}', but it is marked as noreturn", state.function.name), state.line));
+				}
+
 				let return_type = match state.function.return_type {
 					Some(ref x) => x.clone(),
 					None => return Err((String::from("attempted to return from function that does not return anything, did you forget to specify the return type in the signature?"), state.line))
 				};
 
+				/* Ok(...) and Err(...) can only be returned from functions that return a Result */
+				match expr.first() {
+					Some(Identifier(x)) if (x == "Ok" || x == "Err") && return_type.result_types().is_none() => {
+						return Err((format!("attempted to return {x}(...) from function '{}', but it returns '{}', which isn't a Result", state.function.name, return_type.string), state.line));
+					}
+					_ => ()
+				}
+
+				/* tuples get returned in rax and rdx, or through memory if they're too big for that */
+				if (return_type.is_tuple()) {
+					let addr = eval_tuple(state, expr, &return_type)?;
+					return_tuple(state, &addr, &return_type);
+					continue;
+				}
+
 				let return_value = eval_expression(state, expr, &return_type)?;
 				let accumulator = get_accumulator(&return_type.word);
 
@@ -319,36 +1151,17 @@ pub fn generate(state: &mut State, input: &[AstType]) -> Result<(), (String, i64
 					state.textsect.push_str(&format!("\tmov {accumulator}, {return_value}\n"));
 				}
 
-				state.textsect.push_str(&format!("\tjmp .ret_{}\n", state.function.name));
+				state.textsect.push_str(&format!("\tjmp {}\n", return_label(state)));
 				state.function.returns = true;
 			}
 			/* ----------------------- */
 			/*      if statements      */
 			/* ----------------------- */
 			IfStatement(expr1, operator, expr2, body) => {
-				let expr_type = infer_type(state, expr1)?;
-
-				let value = eval_expression(state, expr1, &expr_type)?;
-				let value2 = eval_expression(state, expr2, &expr_type)?;
-
-				let accumulator = get_accumulator(&expr_type.word);
-
-				if (value != accumulator) {
-					state.textsect.push_str(&format!("\tmov {accumulator}, {} {value}\n", expr_type.word));
-				}
-				
-				state.textsect.push_str(&format!("\tcmp {accumulator}, {value2}\n"));
-				
-				let jump_instruction = match operator {
-					ComparisonOperator::Equal => "jne",
-					ComparisonOperator::NotEqual => "je",
+				compare(state, expr1, expr2)?;
 
-					ComparisonOperator::GreaterThan => "jle",
-					ComparisonOperator::LessThan => "jge",
-
-					ComparisonOperator::GreaterThanEqual => "jl",
-					ComparisonOperator::LessThanEqual => "jg",
-				};
+				/* we jump over the body if the comparison is false */
+				let jump_instruction = jump_instruction(operator, false);
 
 				let old_labels = state.labels;
 
@@ -363,11 +1176,25 @@ pub fn generate(state: &mut State, input: &[AstType]) -> Result<(), (String, i64
 			/* --------------------------- */
 			VariableDefinition(name, vartype, initexpr) => {
 				let vartype = match vartype {
-					Some(x) => DataType::new(x, state.line)?,
+					Some(x) => resolve_type(state, x)?,
 					None => infer_type(state, &initexpr.clone().unwrap())? /* unwrap will never fail */ 
 				};
 
-				if let Some(initexpr) = initexpr {
+				if (vartype.is_tuple()) {
+					let value = match initexpr {
+						Some(x) => Some(eval_tuple(state, x, &vartype)?),
+						None => None
+					};
+
+					add_variable(state, name, &vartype, None)?;
+
+					if let Some(value) = value {
+						/* unwrap will never fail, we just added the variable */
+						let addr = state.function.local_variables.get(name).unwrap().addr.clone();
+						copy_tuple(state, &value, &addr, &vartype);
+					}
+				}
+				else if let Some(initexpr) = initexpr {
 					let value = eval_expression(state, initexpr, &vartype)?;
 					add_variable(state, name, &vartype, Some(&value))?;
 				}
@@ -375,6 +1202,41 @@ pub fn generate(state: &mut State, input: &[AstType]) -> Result<(), (String, i64
 					add_variable(state, name, &vartype, None)?;
 				}
 			},
+			/* -------------------------- */
+			/*    tuple destructuring     */
+			/* -------------------------- */
+			TupleDestructuring(names, initexpr) => {
+				let tuple_type = infer_type(state, initexpr)?;
+
+				if (!tuple_type.is_tuple()) {
+					return Err((format!("attempted to destructure a value of type '{}', but it is not a tuple", tuple_type.string), state.line));
+				}
+
+				if (names.len() != tuple_type.members.len()) {
+					return Err((format!("attempted to destructure a tuple of type '{}' into {} variables", tuple_type.string, names.len()), state.line));
+				}
+
+				let value = eval_tuple(state, initexpr, &tuple_type)?;
+
+				for (i, offset) in tuple_type.member_offsets().into_iter().enumerate() {
+					let member_type = &tuple_type.members[i];
+					let member = member_address(&value, offset);
+
+					if (member_type.is_tuple()) {
+						add_variable(state, &names[i], member_type, None)?;
+
+						/* unwrap will never fail, we just added the variable */
+						let addr = state.function.local_variables.get(&names[i]).unwrap().addr.clone();
+						copy_tuple(state, &member, &addr, member_type);
+						continue;
+					}
+
+					let accumulator = get_accumulator(&member_type.word);
+					state.textsect.push_str(&format!("\tmov {accumulator}, {} {member}\n", member_type.word));
+
+					add_variable(state, &names[i], member_type, Some(accumulator))?;
+				}
+			}
 			/* -------------------------*/ 
 			/*    variable assignment   */ 
 			/* -------------------------*/ 
@@ -384,14 +1246,23 @@ pub fn generate(state: &mut State, input: &[AstType]) -> Result<(), (String, i64
 					None => return Err((format!("attempted to assign a value to variable '{name}', but it is not defined in the current scope"), state.line))
 				};
 
-				let value = eval_expression(state, expr, &variable.vartype)?;
-				state.textsect.push_str(&format!("\tmov {} {}, {value}\n", variable.vartype.word, variable.addr));
+				if (variable.vartype.is_tuple()) {
+					let value = eval_tuple(state, expr, &variable.vartype)?;
+					copy_tuple(state, &value, &variable.addr, &variable.vartype);
+					continue;
+				}
+
+				store_expression(state, expr, &variable.vartype, &variable.addr)?;
 			}
 			/* -------------------------- */
 			/*           macros           */
 			/* -------------------------- */
 			MacroCall(name, args) => {
 				macros::call_macro(state, name, args)?;
+
+				if (name == "panic!") {
+					unreachable_after = Some(name);
+				}
 			},
 		}
 	}
@@ -0,0 +1,105 @@
+use super::*;
+
+/* the arithmetic operators can be overloaded for user types by implementing these traits, which are always defined: */
+/*
+	trait Add { fn add(self, other: Self) -> Self }
+	trait Sub { fn sub(self, other: Self) -> Self }
+	trait Mul { fn mul(self, other: Self) -> Self }
+	trait Div { fn div(self, other: Self) -> Self }
+*/
+/* 'a + b' with a and b being a type that implements Add turns into a call to its add method, before the expression gets evaluated */
+/* newtypes of integers that don't implement an operator still get the builtin one, but tuples don't have any builtin operators */
+
+/* the trait and the method that an operator calls */
+fn operator_trait(token: &Token) -> Option<(&'static str, &'static str)> {
+	match token {
+		Operator(Plus) => Some(("Add", "add")),
+		Operator(Dash) => Some(("Sub", "sub")),
+		Operator(Star) => Some(("Mul", "mul")),
+		Operator(Slash) => Some(("Div", "div")),
+		_ => None
+	}
+}
+
+pub fn is_operator_trait(name: &str) -> bool {
+	matches!(name, "Add" | "Sub" | "Mul" | "Div")
+}
+
+pub fn define_operator_traits(state: &mut State) {
+	for (name, method) in [("Add", "add"), ("Sub", "sub"), ("Mul", "mul"), ("Div", "div")] {
+		state.traits.insert(name.to_owned(), vec![TraitMethod {
+			name: method.to_owned(),
+			arg_types: vec!["Self".to_owned(), "Self".to_owned()],
+			return_type: Some("Self".to_owned())
+		}]);
+	}
+}
+
+/* splits an expression at the operators that aren't inside of parentheses, so 'a + f(b, c) * d' becomes [a], +, [f(b, c)], *, [d] */
+fn split_operands(expr: &Expression) -> (Vec<Expression>, Vec<Token>) {
+	let mut operands: Vec<Expression> = vec![Vec::new()];
+	let mut operators: Vec<Token> = Vec::new();
+	let mut depth = 0;
+
+	/* unwrap will never fail, there's always at least one operand */
+	for i in expr {
+		match i {
+			Operator(LeftParen) | Operator(LeftSquare) => depth += 1,
+			Operator(RightParen) | Operator(RightSquare) => depth -= 1,
+			/* a - at the start of an operand is a unary minus, like in 'a * -b' */
+			_ if depth == 0 && operator_trait(i).is_some() && !operands.last().unwrap().is_empty() => {
+				operators.push(i.clone());
+				operands.push(Vec::new());
+				continue;
+			}
+			_ => ()
+		}
+
+		operands.last_mut().unwrap().push(i.clone());
+	}
+
+	(operands, operators)
+}
+
+/* turns every overloaded operator in an expression of type 'datatype' into a call to the method that implements it */
+/* expressions are evaluated from left to right, so 'a + b * c' becomes 'T::mul(T::add(a, b), c)' */
+pub fn lower_operators(state: &State, expr: &Expression, datatype: &DataType) -> Result<Expression, (String, i64)> {
+	let (mut operands, operators) = split_operands(expr);
+	if (operators.is_empty()) {
+		return Ok(expr.clone());
+	}
+
+	let mut output = operands.remove(0);
+
+	for (operator, operand) in operators.iter().zip(operands) {
+		/* unwrap will never fail, split_operands only splits at operators that have a trait */
+		let (trait_name, method) = operator_trait(operator).unwrap();
+		let key = (datatype.string.clone(), method.to_owned());
+
+		let function = match state.methods.get(&key) {
+			/* inside of the method that implements an operator, the operator is the builtin one, otherwise it would call itself forever */
+			Some(x) if x.function == state.function.name && !datatype.is_tuple() => None,
+			Some(x) if state.impls.contains(&(trait_name.to_owned(), datatype.string.clone())) => Some(x.function.clone()),
+			_ => None
+		};
+
+		match function {
+			Some(function) => {
+				let mut call = vec![Identifier(function), Operator(LeftParen)];
+				call.append(&mut output);
+				call.push(Operator(Comma));
+				call.extend(operand);
+				call.push(Operator(RightParen));
+
+				output = call;
+			}
+			None if datatype.is_tuple() => return Err((format!("type '{}' does not implement trait {trait_name}, so {operator} can't be used on it", datatype.string), state.line)),
+			None => {
+				output.push(operator.clone());
+				output.extend(operand);
+			}
+		}
+	}
+
+	Ok(output)
+}
@@ -29,6 +29,16 @@ pub fn get_accumulator(word: &WordType) -> &'static str {
 	}
 }
 
+/* the instruction that sign extends the accumulator into rdx (or ah) before idiv */
+pub fn get_sign_extension(word: &WordType) -> &'static str {
+	match word {
+		Byte => "cbw",
+		Word => "cwd",
+		DoubleWord => "cdq",
+		QuadWord => "cqo",
+	}
+}
+
 pub fn get_rbx(word: &WordType) -> &'static str {
 	match word {
 		Byte => "bl",
@@ -47,6 +57,14 @@ pub fn get_r11(word: &WordType) -> &'static str {
 	}
 }
 
+/* returns true if the input is one of the registers that the codegen can leave a value in */
+/* the codegen uses this to figure out whether a value would get overwritten by evaluating something else */
+pub fn is_register(input: &str) -> bool {
+	[Byte, Word, DoubleWord, QuadWord].iter().any(|word| {
+		input == get_accumulator(word) || input == get_rbx(word) || input == get_r11(word) || (0..6).any(|i| input == get_register(i, word))
+	})
+}
+
 pub fn get_register(argument_count: usize, word: &WordType) -> &'static str {
     match (argument_count, word) {
         /* edi/rdi */
@@ -87,4 +105,44 @@ pub fn get_register(argument_count: usize, word: &WordType) -> &'static str {
 
         (x, _) => panic!("called get_register in an attempt to get the register for the {0}nd argument, this should never happen as a function that has more than 6 arguments should push them on the stack", x+1)
     }
-}
\ No newline at end of file
+}
+
+/* every general purpose register, as (64 bit, 32 bit, 16 bit, 8 bit) */
+const GENERAL_PURPOSE_REGISTERS: [(&str, &str, &str, &str); 16] = [
+	("rax", "eax", "ax", "al"),
+	("rbx", "ebx", "bx", "bl"),
+	("rcx", "ecx", "cx", "cl"),
+	("rdx", "edx", "dx", "dl"),
+	("rsi", "esi", "si", "sil"),
+	("rdi", "edi", "di", "dil"),
+	("rbp", "ebp", "bp", "bpl"),
+	("rsp", "esp", "sp", "spl"),
+	("r8", "r8d", "r8w", "r8b"),
+	("r9", "r9d", "r9w", "r9b"),
+	("r10", "r10d", "r10w", "r10b"),
+	("r11", "r11d", "r11w", "r11b"),
+	("r12", "r12d", "r12w", "r12b"),
+	("r13", "r13d", "r13w", "r13b"),
+	("r14", "r14d", "r14w", "r14b"),
+	("r15", "r15d", "r15w", "r15b"),
+];
+
+/* returns the 64 bit register that a register is a part of, like rax for eax, or None if it isn't a general purpose register */
+pub fn full_register(register: &str) -> Option<&'static str> {
+	GENERAL_PURPOSE_REGISTERS.iter()
+		.find(|(q, d, w, b)| [q, d, w, b].iter().any(|x| x.eq_ignore_ascii_case(register)))
+		.map(|x| x.0)
+}
+
+/* returns the part of a 64 bit register with the size of 'word', like eax for rax and DoubleWord */
+pub fn sized_register(register: &str, word: &WordType) -> &'static str {
+	/* unwrap will never fail, this only gets called with registers that full_register returned */
+	let (q, d, w, b) = GENERAL_PURPOSE_REGISTERS.iter().find(|x| x.0 == register).unwrap();
+
+	match word {
+		Byte => b,
+		Word => w,
+		DoubleWord => d,
+		QuadWord => q
+	}
+}
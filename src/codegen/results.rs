@@ -0,0 +1,179 @@
+use super::*;
+
+/* functions that can fail return a Result, which is either Ok with a value or Err with an error: */
+/*
+	fn open(path: i64) -> Result<i64, i64> {
+		let fd: i64 = syscall!(open, path, 0, 0)
+		if (fd < 0) {
+			return Err(fd)
+		}
+
+		return Ok(fd)
+	}
+*/
+/* a ? after a Result, like 'open(path)?', evaluates to the value if it's Ok, and returns the error from the function it's in if it's Err */
+/* so it can only be used in functions that return a Result with the same type of error */
+/* the places that actually deal with the error use is_ok!, is_err!, unwrap! and unwrap_err! (see macros.rs) */
+
+/* evaluates Ok(x) or Err(x) into a new Result of type 'expected_type', and returns its address */
+pub fn construct_result(state: &mut State, variant: &str, args: &[Expression], expected_type: &DataType) -> Result<String, (String, i64)> {
+	let (ok, err) = match expected_type.result_types() {
+		Some((ok, err)) => (ok.clone(), err.clone()),
+		None => return Err((format!("expected expression to evaluate to type '{}', but {variant}(...) is a Result", expected_type.string), state.line))
+	};
+
+	let value = match args {
+		[x] => x,
+		_ => return Err((format!("{variant}(...) takes 1 value, not {}", args.len()), state.line))
+	};
+
+	let (tag, member_type) = if (variant == "Ok") {
+		(0, ok)
+	}
+	else {
+		(1, err)
+	};
+
+	let addr = reserve_stack(state, expected_type);
+	state.textsect.push_str(&format!("\tmov qword {addr}, {tag}\n"));
+	store_expression(state, value, &member_type, &member_address(&addr, expected_type.member_offsets()[tag + 1]))?;
+
+	Ok(addr)
+}
+
+/* evaluates the Result in 'expr?' and returns from the current function if it's an Err */
+/* if it's Ok, the value in it is put in a hidden variable, and the name of that variable is returned */
+fn try_result(state: &mut State, expr: &Expression) -> Result<String, (String, i64)> {
+	if (expr.is_empty()) {
+		return Err(("expected a Result before '?'".to_owned(), state.line));
+	}
+
+	let result_type = infer_type(state, expr)?;
+	let (ok, err) = match result_type.result_types() {
+		Some((ok, err)) => (ok.clone(), err.clone()),
+		None => return Err((format!("'?' can only be used on a Result, but it was used on a value of type '{}'", result_type.string), state.line))
+	};
+
+	/* the error gets returned as it is, so the function has to return a Result with the same type of error */
+	let return_type = match &state.function.return_type {
+		_ if state.function.noreturn => return Err((format!("'?' cannot be used in function '{}', because it is marked as noreturn", state.function.name), state.line)),
+		Some(x) if x.result_types().is_some_and(|(_, x)| x == &err) => x.clone(),
+		Some(x) => return Err((format!("'?' would return the '{}' error of a '{}' from function '{}', but it returns '{}'", err.string, result_type.string, state.function.name, x.string), state.line)),
+		None => return Err((format!("'?' can only be used in functions that return a Result, but function '{}' does not return anything", state.function.name), state.line))
+	};
+
+	let addr = eval_tuple(state, expr, &result_type)?;
+	let offsets = result_type.member_offsets();
+
+	state.labels += 1;
+	let ok_label = format!(".L{}", state.labels);
+
+	state.textsect.push_str(&format!("\tcmp qword {addr}, 0\n"));
+	state.textsect.push_str(&format!("\tje {ok_label}\n"));
+
+	/* if the Ok types are different, the error has to be moved into the Result that the function returns */
+	let error = if (return_type == result_type) {
+		addr.clone()
+	}
+	else {
+		let error = reserve_stack(state, &return_type);
+		state.textsect.push_str(&format!("\tmov qword {error}, 1\n"));
+		copy_tuple(state, &member_address(&addr, offsets[2]), &member_address(&error, return_type.member_offsets()[2]), &err);
+
+		error
+	};
+
+	return_tuple(state, &error, &return_type);
+	state.textsect.push_str(&format!("{ok_label}:\n"));
+
+	/* the name can't be typed in a source file, so it can never clash with a real variable */
+	let name = format!("?{}", state.labels);
+	state.function.local_variables.insert(name.clone(), Variable { addr: member_address(&addr, offsets[1]), vartype: ok });
+
+	Ok(name)
+}
+
+/* turns every ? in an expression into a check that returns the error if the Result before it is an Err */
+/* the Result and the ? get replaced with a hidden variable that holds the value, so 'read(fd)? + 1' becomes something like '?3 + 1' */
+/* a ? that's inside of parentheses (or a branch of an if expression) gets dealt with when whatever is in them gets evaluated */
+pub fn lower_try(state: &mut State, expr: &Expression) -> Result<Expression, (String, i64)> {
+	if (!expr.iter().any(|x| matches!(x, Operator(Question)))) {
+		return Ok(expr.clone());
+	}
+
+	let mut output: Expression = Vec::new();
+	let mut depth = 0;
+
+	for token in expr {
+		match token {
+			Operator(LeftParen) | Operator(LeftSquare) | Operator(LeftCurly) => depth += 1,
+			Operator(RightParen) | Operator(RightSquare) | Operator(RightCurly) => depth -= 1,
+			Operator(Question) if depth == 0 => {
+				let operand = output.split_off(receiver_start(&output));
+				output.push(Identifier(try_result(state, &operand)?));
+				continue;
+			}
+			_ => ()
+		}
+
+		output.push(token.clone());
+	}
+
+	Ok(output)
+}
+
+/* returns the type of the first value of an expression if there's a ? right after it, which is the type of Ok */
+/* so with 'fn read(fd: i64) -> Result<i64, i64>', 'read(fd)? + 1' is an i64 */
+pub fn infer_try_type(state: &mut State, expr: &Expression) -> Result<Option<DataType>, (String, i64)> {
+	let mut depth = 0;
+	let mut question = None;
+
+	for (i, v) in expr.iter().enumerate() {
+		match v {
+			Operator(LeftParen) | Operator(LeftSquare) | Operator(LeftCurly) => depth += 1,
+			Operator(RightParen) | Operator(RightSquare) | Operator(RightCurly) => depth -= 1,
+			Operator(Question) if depth == 0 => {
+				question = Some(i);
+				break;
+			}
+			_ => ()
+		}
+	}
+
+	/* the ? has to come right after the first value, otherwise it belongs to some other value */
+	let operand = match question {
+		Some(x) if x > 0 && receiver_start(&expr[..x]) == 0 => &expr[..x],
+		_ => return Ok(None)
+	};
+
+	let result_type = infer_type(state, &operand.to_vec())?;
+	match result_type.result_types() {
+		Some((ok, _)) => Ok(Some(ok.clone())),
+		None => Err((format!("'?' can only be used on a Result, but it was used on a value of type '{}'", result_type.string), state.line))
+	}
+}
+
+/* a call with a ? on its own line, like 'write(fd, buf)?', which only gets checked for an error */
+pub fn try_statement(state: &mut State, expr: &Expression) -> Result<(), (String, i64)> {
+	let lowered = lower_method_calls(state, expr)?;
+
+	match lower_try(state, &lowered)?.as_slice() {
+		[Identifier(_)] => Ok(()),
+		_ => Err(("expected a call followed by '?', like 'write(fd, buf)?', the value of anything else can only be used in an expression".to_owned(), state.line))
+	}
+}
+
+/* evaluates the argument of is_ok!, is_err!, unwrap! and unwrap_err!, and returns its address and its type */
+pub fn eval_result_argument(state: &mut State, name: &str, args: &[Expression]) -> Result<(String, DataType), (String, i64)> {
+	let arg = match args {
+		[x] => x,
+		_ => return Err((format!("{name} macro accepts 1 argument, not {}", args.len()), state.line))
+	};
+
+	let result_type = infer_type(state, arg)?;
+	if (result_type.result_types().is_none()) {
+		return Err((format!("the argument of {name} has to be a Result, but it was given a value of type '{}'", result_type.string), state.line));
+	}
+
+	Ok((eval_tuple(state, arg, &result_type)?, result_type))
+}
@@ -0,0 +1,191 @@
+use super::*;
+
+/* small routines that the code generated by some macros calls, like print! */
+/* they only get added to the output if something uses them, and they only use syscalls, so they work with and without libc */
+/* they don't touch rbx, so they can be called in the middle of an expression */
+
+/* they all take the file descriptor to write to in rdi */
+
+/* writes rdx bytes starting at rsi, the arguments are the same as the write syscall */
+const WRITE: &str = "catlang_write:
+	mov rax, 1
+	syscall
+	ret
+
+";
+
+/* writes the null terminated string at rsi */
+const WRITE_CSTR: &str = "catlang_write_cstr:
+	xor edx, edx
+.length:
+	cmp byte [rsi+rdx], 0
+	je .write
+	inc rdx
+	jmp .length
+.write:
+	mov rax, 1
+	syscall
+	ret
+
+";
+
+/* writes the signed integer in rsi, in decimal */
+/* the digits get written backwards into a buffer on the stack, starting at the end */
+const WRITE_INT: &str = "catlang_write_int:
+	sub rsp, 32
+	mov r8, rsi
+	mov rax, rsi
+	lea rsi, [rsp+32]
+	mov rcx, 10
+	test rax, rax
+	jns .digits
+	neg rax
+.digits:
+	xor edx, edx
+	div rcx
+	add dl, 48
+	dec rsi
+	mov byte [rsi], dl
+	test rax, rax
+	jnz .digits
+	test r8, r8
+	jns .write_digits
+	dec rsi
+	mov byte [rsi], 45
+.write_digits:
+	lea rdx, [rsp+32]
+	sub rdx, rsi
+	mov rax, 1
+	syscall
+	add rsp, 32
+	ret
+
+";
+
+/* the allocator gets its memory straight from the kernel with mmap, every allocation is its own mapping */
+/* the size of the mapping is stored in the 16 bytes in front of the memory that gets returned, so that free and realloc know how big it is */
+/* (16 instead of 8 so that the memory stays aligned to 16 bytes) */
+
+/* allocates rdi bytes and returns a pointer to them in rax, or 0 if there isn't enough memory */
+const ALLOC: &str = "catlang_alloc:
+	lea rsi, [rdi+16]
+	push rsi
+	xor edi, edi
+	mov edx, 3
+	mov r10d, 34
+	mov r8, -1
+	xor r9d, r9d
+	mov rax, 9
+	syscall
+	pop rsi
+	cmp rax, -4095
+	jae .alloc_failed
+	mov qword [rax], rsi
+	add rax, 16
+	ret
+.alloc_failed:
+	xor eax, eax
+	ret
+
+";
+
+/* resizes the memory at rdi to rsi bytes, and returns the new pointer in rax (or 0 if there isn't enough memory, in which case the old memory is still there) */
+/* the contents get moved over by the kernel */
+const REALLOC: &str = "catlang_realloc:
+	test rdi, rdi
+	jnz .realloc_existing
+	mov rdi, rsi
+	jmp catlang_alloc
+.realloc_existing:
+	lea rdx, [rsi+16]
+	push rdx
+	sub rdi, 16
+	mov rsi, qword [rdi]
+	mov r10d, 1
+	mov rax, 25
+	syscall
+	pop rdx
+	cmp rax, -4095
+	jae .realloc_failed
+	mov qword [rax], rdx
+	add rax, 16
+	ret
+.realloc_failed:
+	xor eax, eax
+	ret
+
+";
+
+/* frees the memory at rdi, freeing 0 does nothing */
+const FREE: &str = "catlang_free:
+	test rdi, rdi
+	jz .free_null
+	sub rdi, 16
+	mov rsi, qword [rdi]
+	mov rax, 11
+	syscall
+.free_null:
+	ret
+
+";
+
+/* returns the message for the errno code in rdi in rax (the pointer) and rdx (the length), negative codes work too */
+/* codes that aren't in the table get the last entry, which is for unknown errors (see syscalls.rs) */
+const ERRNO_STR: &str = "catlang_errno_str:
+	mov rax, rdi
+	test rax, rax
+	jns .positive
+	neg rax
+.positive:
+	cmp rax, qword [catlang_errno_count]
+	jb .known
+	mov rax, qword [catlang_errno_count]
+.known:
+	shl rax, 4
+	mov rdx, qword [catlang_errno_table+rax+8]
+	mov rax, qword [catlang_errno_table+rax]
+	ret
+
+";
+
+/* returns the code of a routine */
+fn routine_code(name: &str) -> &'static str {
+	match name {
+		"catlang_write" => WRITE,
+		"catlang_write_cstr" => WRITE_CSTR,
+		"catlang_write_int" => WRITE_INT,
+		"catlang_alloc" => ALLOC,
+		"catlang_realloc" => REALLOC,
+		"catlang_free" => FREE,
+		"catlang_errno_str" => ERRNO_STR,
+		_ => unreachable!("runtime routine {name} does not exist")
+	}
+}
+
+/* writes a call to a runtime routine, and makes sure that it ends up in the output */
+pub fn call_routine(state: &mut State, name: &'static str) {
+	state.runtime.insert(name);
+
+	/* realloc of a null pointer jumps to alloc */
+	if (name == "catlang_realloc") {
+		state.runtime.insert("catlang_alloc");
+	}
+
+	state.function.calls_funcs = true;
+
+	state.textsect.push_str(&format!("\tcall {name}\n"));
+}
+
+/* appends every routine that got called somewhere to the text section, this is done after everything else is generated */
+pub fn emit_runtime(state: &mut State) {
+	let mut routines: Vec<&str> = state.runtime.iter().copied().collect();
+	routines.sort();
+
+	for i in routines {
+		state.textsect.push_str(routine_code(i));
+	}
+
+	if (state.runtime.contains("catlang_errno_str")) {
+		state.rodata.push_str(&errno_table());
+	}
+}
@@ -0,0 +1,240 @@
+use super::*;
+
+/* syscall! takes the syscall either as a number or by its name, so these do the same thing: */
+/*
+	syscall!(1, 1, buf, len)
+	syscall!(write, 1, buf, len)
+*/
+/* the syscalls that have a name are in this table, and calling one of them with the wrong amount of arguments is an error */
+/* a variable with the same name as a syscall still means the variable, so existing code that has a 'write' variable keeps working */
+
+/* (name, number, the amount of arguments it takes), for x86_64 linux */
+type Syscall = (&'static str, i64, usize);
+
+const SYSCALLS: &[Syscall] = &[
+	("read", 0, 3),
+	("write", 1, 3),
+	("open", 2, 3),
+	("close", 3, 1),
+	("stat", 4, 2),
+	("fstat", 5, 2),
+	("lstat", 6, 2),
+	("poll", 7, 3),
+	("lseek", 8, 3),
+	("mmap", 9, 6),
+	("mprotect", 10, 3),
+	("munmap", 11, 2),
+	("brk", 12, 1),
+	("rt_sigaction", 13, 4),
+	("rt_sigprocmask", 14, 4),
+	("ioctl", 16, 3),
+	("pread64", 17, 4),
+	("pwrite64", 18, 4),
+	("readv", 19, 3),
+	("writev", 20, 3),
+	("access", 21, 2),
+	("pipe", 22, 1),
+	("select", 23, 5),
+	("sched_yield", 24, 0),
+	("mremap", 25, 5),
+	("msync", 26, 3),
+	("madvise", 28, 3),
+	("dup", 32, 1),
+	("dup2", 33, 2),
+	("pause", 34, 0),
+	("nanosleep", 35, 2),
+	("getpid", 39, 0),
+	("socket", 41, 3),
+	("connect", 42, 3),
+	("accept", 43, 3),
+	("sendto", 44, 6),
+	("recvfrom", 45, 6),
+	("shutdown", 48, 2),
+	("bind", 49, 3),
+	("listen", 50, 2),
+	("clone", 56, 5),
+	("fork", 57, 0),
+	("vfork", 58, 0),
+	("execve", 59, 3),
+	("exit", 60, 1),
+	("wait4", 61, 4),
+	("kill", 62, 2),
+	("uname", 63, 1),
+	("fcntl", 72, 3),
+	("flock", 73, 2),
+	("fsync", 74, 1),
+	("truncate", 76, 2),
+	("ftruncate", 77, 2),
+	("getcwd", 79, 2),
+	("chdir", 80, 1),
+	("rename", 82, 2),
+	("mkdir", 83, 2),
+	("rmdir", 84, 1),
+	("creat", 85, 2),
+	("link", 86, 2),
+	("unlink", 87, 1),
+	("symlink", 88, 2),
+	("readlink", 89, 3),
+	("chmod", 90, 2),
+	("chown", 92, 3),
+	("umask", 95, 1),
+	("gettimeofday", 96, 2),
+	("getuid", 102, 0),
+	("getgid", 104, 0),
+	("geteuid", 107, 0),
+	("getegid", 108, 0),
+	("getppid", 110, 0),
+	("setsid", 112, 0),
+	("arch_prctl", 158, 2),
+	("gettid", 186, 0),
+	("time", 201, 1),
+	("futex", 202, 6),
+	("getdents64", 217, 3),
+	("clock_gettime", 228, 2),
+	("clock_nanosleep", 230, 4),
+	("exit_group", 231, 1),
+	("openat", 257, 4),
+	("mkdirat", 258, 3),
+	("unlinkat", 263, 3),
+	("renameat", 264, 4),
+	("pipe2", 293, 2),
+	("getrandom", 318, 3)
+];
+
+/* the messages for errno codes, errno_str!(2) is ERRNO[2], the same ones that strerror in glibc uses */
+const ERRNO: &[&str] = &[
+	"Success",
+	"Operation not permitted",
+	"No such file or directory",
+	"No such process",
+	"Interrupted system call",
+	"Input/output error",
+	"No such device or address",
+	"Argument list too long",
+	"Exec format error",
+	"Bad file descriptor",
+	"No child processes",
+	"Resource temporarily unavailable",
+	"Cannot allocate memory",
+	"Permission denied",
+	"Bad address",
+	"Block device required",
+	"Device or resource busy",
+	"File exists",
+	"Invalid cross-device link",
+	"No such device",
+	"Not a directory",
+	"Is a directory",
+	"Invalid argument",
+	"Too many open files in system",
+	"Too many open files",
+	"Inappropriate ioctl for device",
+	"Text file busy",
+	"File too large",
+	"No space left on device",
+	"Illegal seek",
+	"Read-only file system",
+	"Too many links",
+	"Broken pipe",
+	"Numerical argument out of domain",
+	"Numerical result out of range",
+	"Resource deadlock avoided",
+	"File name too long",
+	"No locks available",
+	"Function not implemented",
+	"Directory not empty",
+	"Too many levels of symbolic links"
+];
+
+const UNKNOWN_ERROR: &str = "Unknown error";
+
+/* returns the number of the syscall that the first argument of syscall! is, and where it is in the table if it's in there */
+fn syscall_number(state: &mut State, expr: &Expression) -> Result<(String, Option<&'static Syscall>), (String, i64)> {
+	match expr.as_slice() {
+		[Identifier(x)] if !state.function.local_variables.contains_key(x) => match SYSCALLS.iter().find(|(name, ..)| name == x) {
+			Some(syscall) => Ok((syscall.1.to_string(), Some(syscall))),
+			None => Err((format!("unknown syscall '{x}', syscalls that aren't in the table of names have to be called by their number"), state.line))
+		},
+		_ => {
+			/* numbers that are in the table get their arguments checked too */
+			let known = match expr.as_slice() {
+				[Numerical(x)] => SYSCALLS.iter().find(|(_, number, _)| x.parse::<i64>() == Ok(*number)),
+				_ => None
+			};
+
+			Ok((eval_expression(state, expr, &DataType::new("i64", state.line)?)?, known))
+		}
+	}
+}
+
+pub fn syscall(state: &mut State, args: &[Expression]) -> Result<Option<String>, (String, i64)> {
+	let (number, args) = match args.split_first() {
+		Some(x) => x,
+		None => return Err((String::from("syscall! needs at least the number or the name of a syscall"), state.line))
+	};
+
+	if (args.len() > 6) {
+		return Err((String::from("syscall! does not take more than 7 arguments"), state.line));
+	}
+
+	let (mut number, known) = syscall_number(state, number)?;
+	if let Some(&(name, _, arity)) = known {
+		if (args.len() != arity) {
+			return Err((format!("syscall '{name}' takes {arity} {}, but syscall! was given {}", if (arity == 1) {
+				"argument"
+			}
+			else {
+				"arguments"
+			}, args.len()), state.line));
+		}
+	}
+
+	/* like with function calls, every argument gets evaluated before any of them go in their registers */
+	/* otherwise an argument that calls a function would overwrite the registers of the ones before it */
+	let i64 = DataType::new("i64", state.line)?;
+	let mut values: Vec<String> = Vec::new();
+
+	if (is_register(&number)) {
+		let addr = reserve_stack(state, &i64);
+		state.textsect.push_str(&format!("\tmov qword {addr}, {number}\n"));
+		number = addr;
+	}
+
+	for i in args {
+		let value = eval_expression(state, i, &i64)?;
+
+		if (is_register(&value)) {
+			let addr = reserve_stack(state, &i64);
+			state.textsect.push_str(&format!("\tmov qword {addr}, {value}\n"));
+			values.push(addr);
+		}
+		else {
+			values.push(value);
+		}
+	}
+
+	for (register, value) in ["rdi", "rsi", "rdx", "r10", "r8", "r9"].iter().zip(values) {
+		state.textsect.push_str(&format!("\tmov {register}, {value}\n"));
+	}
+	state.textsect.push_str(&format!("\tmov rax, {number}\n"));
+	state.textsect.push_str("\tsyscall\n\n");
+
+	Ok(Some(String::from("rax")))
+}
+
+/* the table that catlang_errno_str (see runtime.rs) looks the messages up in, it's a (pointer, length) for every errno code */
+/* and one more at the end for the codes that aren't in it */
+pub fn errno_table() -> String {
+	let mut output = format!("\tcatlang_errno_count:\n\t\tdq {}\n", ERRNO.len());
+	output.push_str("\tcatlang_errno_table:\n");
+
+	for (i, v) in ERRNO.iter().chain([&UNKNOWN_ERROR]).enumerate() {
+		output.push_str(&format!("\t\tdq catlang_errno_{i}, {}\n", v.len()));
+	}
+
+	for (i, v) in ERRNO.iter().chain([&UNKNOWN_ERROR]).enumerate() {
+		output.push_str(&format!("\tcatlang_errno_{i}:\n\t\tdb `{v}`\n"));
+	}
+
+	output
+}
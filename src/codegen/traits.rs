@@ -0,0 +1,283 @@
+use super::*;
+
+/* a trait is a set of methods that a type can implement: */
+/*
+	trait Writer {
+		fn write(self, buf: i64, n: i64) -> i64
+	}
+
+	impl Writer for File {
+		fn write(self, buf: i64, n: i64) -> i64 { ... }
+	}
+*/
+/* generic functions can require their type paramaters to implement traits, like fn emit<T: Writer>(w: T) */
+/* and then call the methods of the trait on them, which get resolved when the function is instantiated (see generics.rs) */
+
+/* a dyn Writer is any type that implements Writer, the method that gets called is only decided while the program runs */
+/* it's laid out like a slice, the first 8 bytes are the value itself and the second 8 bytes are a pointer to the vtable */
+/* the vtable is in .rodata and has the address of every method of the trait for that type, in the order that the trait has them */
+/* calling a method on a dyn passes the value as self and calls whatever is in the vtable, so only types that fit in 8 bytes can be a dyn */
+
+#[derive(Clone)]
+pub struct TraitMethod {
+	pub name: String,
+	/* Self stands for the type that implements the trait, the first one is always Self */
+	pub arg_types: Vec<String>,
+	pub return_type: Option<String>
+}
+
+/* returns the signature of a method for error messages, like fn(File, i64) -> i64 */
+fn signature(arg_types: &[String], return_type: &Option<String>) -> String {
+	match return_type {
+		Some(x) => format!("fn({}) -> {x}", arg_types.join(", ")),
+		None => format!("fn({})", arg_types.join(", "))
+	}
+}
+
+fn vtable_label(trait_name: &str, datatype: &DataType) -> String {
+	format!("vtable.{trait_name}.{}", mangle_type(&datatype.string))
+}
+
+/* methods that take or return Self anywhere other than their receiver can't be called on a dyn, since the dyn doesn't know what Self is */
+fn is_dyn_compatible(method: &TraitMethod) -> bool {
+	let bindings = HashMap::from([("Self".to_owned(), "#".to_owned())]);
+	let mentions_self = |x: &String| substitute_type(x, &bindings) != *x;
+
+	!method.arg_types[1..].iter().any(mentions_self) && !method.return_type.iter().any(mentions_self)
+}
+
+pub fn define_trait(state: &mut State, name: &str, body: &[AstType]) -> Result<(), (String, i64)> {
+	if (!state.function.name.is_empty()) {
+		return Err((format!("cannot define trait {name} inside of a function, traits have to be defined at the top level of a file"), state.line));
+	}
+
+	if (state.traits.contains_key(name)) {
+		return Err((format!("trait {name} is already defined"), state.line));
+	}
+
+	if (resolve_type(state, name).is_ok()) {
+		return Err((format!("cannot define trait {name}, there's already a type with that name"), state.line));
+	}
+
+	let mut methods: Vec<TraitMethod> = Vec::new();
+	for i in body {
+		match i {
+			FunctionPrototype(method, arg_types, return_type) => {
+				if (arg_types.first().map(|x| x.as_str()) != Some("Self")) {
+					return Err((format!("method '{method}' of trait {name} has to take self as its first paramater"), state.line));
+				}
+
+				if (methods.iter().any(|x| &x.name == method)) {
+					return Err((format!("method '{method}' is defined more than once in trait {name}"), state.line));
+				}
+
+				methods.push(TraitMethod { name: method.clone(), arg_types: arg_types.clone(), return_type: return_type.clone() });
+			}
+			AstType::Newline => state.line += 1,
+			_ => return Err((format!("only method prototypes, like 'fn write(self, buf: i64, n: i64) -> i64', can be in trait {name}"), state.line))
+		}
+	}
+
+	/* the methods of a dyn get called through the vtable */
+	let dyn_name = format!("dyn {name}");
+	state.types.insert(dyn_name.clone(), DataType::slice(&dyn_name));
+
+	let bindings = HashMap::from([("Self".to_owned(), dyn_name.clone())]);
+	for (slot, method) in methods.iter().enumerate() {
+		if (!is_dyn_compatible(method)) {
+			continue;
+		}
+
+		let function = format!("{name}::{}", method.name);
+		let return_type = match &method.return_type {
+			Some(x) => Some(resolve_type(state, x)?),
+			None => None
+		};
+
+		state.methods.insert((dyn_name.clone(), method.name.clone()), Method { function: function.clone(), takes_self: true });
+		state.functions.insert(function, Function {
+			arg_types: method.arg_types.iter().map(|x| substitute_type(x, &bindings)).collect(),
+			return_type,
+			public: true,
+			file: state.file.clone(),
+			attributes: Vec::new(),
+			inline: None,
+			vtable_slot: Some(slot)
+		});
+	}
+
+	state.traits.insert(name.to_owned(), methods);
+
+	Ok(())
+}
+
+/* checks that the functions in 'impl trait_name for type_name' are exactly the methods of the trait, with the same signatures */
+pub fn check_impl(state: &State, trait_name: &str, type_name: &str, body: &[AstType]) -> Result<(), (String, i64)> {
+	let methods = match state.traits.get(trait_name) {
+		Some(x) => x,
+		None => return Err((format!("trait {trait_name} is not defined"), state.line))
+	};
+
+	let datatype = resolve_type(state, type_name)?;
+	if (state.impls.contains(&(trait_name.to_owned(), datatype.string.clone()))) {
+		return Err((format!("trait {trait_name} is already implemented for type '{}'", datatype.string), state.line));
+	}
+
+	/* the builtin operators of integers can't be replaced, newtypes are for that */
+	if (is_operator_trait(trait_name) && !datatype.is_tuple() && DataType::new(&datatype.string, state.line).is_ok()) {
+		return Err((format!("cannot implement {trait_name} for the builtin type '{}', implement it for a newtype of it instead", datatype.string), state.line));
+	}
+
+	let bindings = HashMap::from([("Self".to_owned(), type_name.to_owned())]);
+	let resolve_all = |types: &[String], return_type: &Option<String>| -> Result<(Vec<DataType>, Option<DataType>), (String, i64)> {
+		let mut resolved: Vec<DataType> = Vec::new();
+		for i in types {
+			resolved.push(resolve_type(state, &substitute_type(i, &bindings))?);
+		}
+
+		Ok((resolved, return_type.as_ref().map(|x| resolve_type(state, &substitute_type(x, &bindings))).transpose()?))
+	};
+
+	/* every function in the impl block, with the line it's on so that errors point at the right function */
+	let mut line = state.line;
+	let mut functions: Vec<(&AstType, i64)> = Vec::new();
+	for i in body {
+		match i {
+			FunctionDefinition(..) => functions.push((i, line)),
+			AstType::Newline => line += 1,
+			_ => ()
+		}
+	}
+
+	let mut defined: Vec<&String> = Vec::new();
+	for (function, line) in functions {
+		let (name, type_params, arg_names, arg_types, return_type) = match function {
+			FunctionDefinition(name, _, _, type_params, (arg_names, arg_types), return_type, _) => (name, type_params, arg_names, arg_types, return_type),
+			_ => unreachable!()
+		};
+		defined.push(name);
+
+		let method = match methods.iter().find(|x| &x.name == name) {
+			Some(x) => x,
+			None => return Err((format!("method '{name}' is not a part of trait {trait_name}, methods that aren't in the trait have to go in a separate 'impl {type_name}' block"), line))
+		};
+
+		if (!type_params.is_empty()) {
+			return Err((format!("method '{name}' in 'impl {trait_name} for {type_name}' cannot be generic, because it isn't in trait {trait_name}"), line));
+		}
+
+		if (arg_names.first().map(|x| x.as_str()) != Some("self")) {
+			return Err((format!("method '{name}' in 'impl {trait_name} for {type_name}' has to take self as its first paramater, like it does in trait {trait_name}"), line));
+		}
+
+		if (resolve_all(arg_types, return_type)? != resolve_all(&method.arg_types, &method.return_type)?) {
+			let substitute_all = |types: &[String], return_type: &Option<String>| signature(
+				&types.iter().map(|x| substitute_type(x, &bindings)).collect::<Vec<String>>(),
+				&return_type.as_ref().map(|x| substitute_type(x, &bindings))
+			);
+
+			return Err((format!("method '{name}' in 'impl {trait_name} for {type_name}' is '{}', but trait {trait_name} expects '{}'", substitute_all(arg_types, return_type), substitute_all(&method.arg_types, &method.return_type)), line));
+		}
+	}
+
+	let missing: Vec<&str> = methods.iter().filter(|x| !defined.contains(&&x.name)).map(|x| x.name.as_str()).collect();
+	if (!missing.is_empty()) {
+		return Err((format!("'impl {trait_name} for {type_name}' is missing {} '{}' of trait {trait_name}", if (missing.len() == 1) {
+			"method"
+		}
+		else {
+			"methods"
+		}, missing.join("', '")), state.line));
+	}
+
+	Ok(())
+}
+
+/* records that a type implements a trait, and writes its vtable to .rodata */
+pub fn add_impl(state: &mut State, trait_name: &str, type_name: &str) -> Result<(), (String, i64)> {
+	let datatype = resolve_type(state, type_name)?;
+	let mut entries: Vec<String> = Vec::new();
+
+	/* unwrap will never fail, check_impl checks that the trait exists */
+	for i in state.traits.get(trait_name).unwrap().clone() {
+		let function = state.methods[&(datatype.string.clone(), i.name)].function.clone();
+
+		/* the vtable counts as calling every method in it */
+		entries.push(symbol_name(state, &function));
+		state.called.insert(function);
+	}
+
+	state.rodata.push_str(&format!("{}:\n\tdq {}\n", vtable_label(trait_name, &datatype), entries.join(", ")));
+	state.impls.insert((trait_name.to_owned(), datatype.string));
+
+	Ok(())
+}
+
+/* turns a value into a dyn, this gets used wherever a dyn Trait is expected but the expression isn't one */
+pub fn coerce_to_dyn(state: &mut State, expr: &Expression, expected_type: &DataType) -> Result<String, (String, i64)> {
+	let trait_name = &expected_type.string["dyn ".len()..];
+	let datatype = infer_type(state, expr)?;
+
+	if (!state.impls.contains(&(trait_name.to_owned(), datatype.string.clone()))) {
+		return Err((format!("expected a '{}', but type '{}' does not implement trait {trait_name}", expected_type.string, datatype.string), state.line));
+	}
+
+	if (datatype.byte_size > 8) {
+		return Err((format!("type '{}' is {} bytes, but only types that fit in 8 bytes can be turned into a '{}'", datatype.string, datatype.byte_size, expected_type.string), state.line));
+	}
+
+	let addr = reserve_stack(state, expected_type);
+	store_expression(state, expr, &datatype, &addr)?;
+
+	state.textsect.push_str(&format!("\tmov rax, {}\n", vtable_label(trait_name, &datatype)));
+	state.textsect.push_str(&format!("\tmov qword {}, rax\n", member_address(&addr, 8)));
+
+	Ok(addr)
+}
+
+/* errors if a type doesn't implement one of the traits that a type paramater requires */
+pub fn check_bounds(state: &State, name: &str, type_param: &str, datatype: &str, bounds: &[String]) -> Result<(), (String, i64)> {
+	for i in bounds {
+		if (!state.impls.contains(&(i.clone(), datatype.to_owned()))) {
+			return Err((format!("type '{datatype}' does not implement trait {i}, which function '{name}' requires for type paramater '{type_param}'"), state.line));
+		}
+	}
+
+	Ok(())
+}
+
+/* gives the placeholder type of a type paramater (see generics.rs) the methods of the traits it has to implement */
+/* so that the body of a generic function can be type checked without knowing what type it'll be instantiated with */
+pub fn add_placeholder_methods(state: &mut State, placeholder: &str, bounds: &[String]) -> Result<(), (String, i64)> {
+	let bindings = HashMap::from([("Self".to_owned(), placeholder.to_owned())]);
+
+	for i in bounds {
+		let methods = match state.traits.get(i) {
+			Some(x) => x.clone(),
+			None => return Err((format!("trait {i} is not defined"), state.line))
+		};
+
+		for method in methods {
+			let function = format!("{placeholder}::{}", method.name);
+			let return_type = match &method.return_type {
+				Some(x) => Some(resolve_type(state, &substitute_type(x, &bindings))?),
+				None => None
+			};
+
+			state.methods.insert((placeholder.to_owned(), method.name.clone()), Method { function: function.clone(), takes_self: true });
+			state.functions.insert(function, Function {
+				arg_types: method.arg_types.iter().map(|x| substitute_type(x, &bindings)).collect(),
+				return_type,
+				public: true,
+				file: state.file.clone(),
+				attributes: Vec::new(),
+				inline: None,
+				vtable_slot: None
+			});
+		}
+
+		state.impls.insert((i.clone(), placeholder.to_owned()));
+	}
+
+	Ok(())
+}
@@ -29,6 +29,11 @@ pub enum Operator {
 
 	LeftAngle, RightAngle,
 
+	LeftSquare, RightSquare,
+	Hash,
+	Dot,
+	Question,
+
 	/* multi character operators */
 	Arrow, /* -> */
 
@@ -42,6 +47,8 @@ pub enum Operator {
 
 	LeftAngleEqual, /* <= */
 	RightAngleEqual, /* >= */
+
+	DotDot, /* .. */
 }
 
 #[derive(Debug, Clone)]
@@ -49,7 +56,15 @@ pub enum Keyword {
 	Let,
 	Fn,
 	Return,
-	If
+	If,
+	Else,
+	Import,
+	Pub,
+	Macro,
+	Type,
+	Newtype,
+	Impl,
+	Trait
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -89,6 +104,27 @@ impl Operator {
 			"<" => Some(LeftAngle),
 			">" => Some(RightAngle),
 
+			"[" => Some(LeftSquare),
+			"]" => Some(RightSquare),
+			"#" => Some(Hash),
+			"." => Some(Dot),
+			"?" => Some(Question),
+
+			"->" => Some(Arrow),
+
+			"*=" => Some(StarEqual),
+			"/=" => Some(SlashEqual),
+			"+=" => Some(PlusEqual),
+			"-=" => Some(DashEqual),
+
+			"==" => Some(DoubleEqual),
+			"!=" => Some(BangEqual),
+
+			"<=" => Some(LeftAngleEqual),
+			">=" => Some(RightAngleEqual),
+
+			".." => Some(DotDot),
+
 			_ => None,
 		}
 	}
@@ -118,6 +154,12 @@ impl Display for Operator {
 			LeftAngle => "<",
 			RightAngle => ">",
 
+			LeftSquare => "[",
+			RightSquare => "]",
+			Hash => "#",
+			Dot => ".",
+			Question => "?",
+
 			Arrow => "->",
 
 			StarEqual => "*=",
@@ -129,7 +171,9 @@ impl Display for Operator {
 			BangEqual => "!=",
 
 			LeftAngleEqual => "<=",
-			RightAngleEqual => ">="
+			RightAngleEqual => ">=",
+
+			DotDot => ".."
 		};
 
 		write!(f, "{}", x)
@@ -143,6 +187,14 @@ impl Keyword {
 			"fn" => Some(Keyword::Fn),
 			"return" => Some(Keyword::Return),
 			"if" => Some(Keyword::If),
+			"else" => Some(Keyword::Else),
+			"import" => Some(Keyword::Import),
+			"pub" => Some(Keyword::Pub),
+			"macro" => Some(Keyword::Macro),
+			"type" => Some(Keyword::Type),
+			"newtype" => Some(Keyword::Newtype),
+			"impl" => Some(Keyword::Impl),
+			"trait" => Some(Keyword::Trait),
 
 			_ => None
 		}
@@ -180,59 +232,18 @@ pub fn print_tokens(input: &Vec<Token>) {
 	println!();
 }
 
-/* this function joins 2 tokens together into one if it finds a certain pattern */
-/* like with for example, multi character operators (arrow operator (->), double equal operator (==)) */
+/* this function joins tokens together into one if it finds a certain pattern */
+/* multi character operators (like -> and ==) are joined while lexing, so that '> =' doesn't become '>=' */
 fn join_tokens(tokens: &mut Vec<Token>) {
-	/* i am pretty sure this doesnt panic */
+	/* join paths to functions in other modules, like io::write, into a single identifier */
 	let mut i = 0;
-	while (i+1 < tokens.len()) {
-		match (&tokens[i], &tokens[i+1]) {
-			/* combine - and > into -> */
-			(Token::Operator(Dash), Token::Operator(RightAngle)) => {
-				tokens[i] = Token::Operator(Arrow);
-				tokens.remove(i+1);
-			}
-			/* combine = and = into == */
-			(Token::Operator(Equal), Token::Operator(Equal)) => {
-				tokens[i] = Token::Operator(DoubleEqual);
-				tokens.remove(i+1);
-			}
-			/* combine ! and = into != */
-			(Token::Operator(Bang), Token::Operator(Equal)) => {
-				tokens[i] = Token::Operator(BangEqual);
-				tokens.remove(i+1);
-			}
-			/* combine < and = into <= */
-			(Token::Operator(LeftAngle), Token::Operator(Equal)) => {
-				tokens[i] = Token::Operator(LeftAngleEqual);
-				tokens.remove(i+1);
-			}
-			/* combine > and = into >= */
-			(Token::Operator(RightAngle), Token::Operator(Equal)) => {
-				tokens[i] = Token::Operator(RightAngleEqual);
-				tokens.remove(i+1);
-			}
-			/* combine * and = into *= */
-			(Token::Operator(Star), Token::Operator(Equal)) => {
-				tokens[i] = Token::Operator(StarEqual);
-				tokens.remove(i+1);
-			}
-			/* combine / and = into /= */
-			(Token::Operator(Slash), Token::Operator(Equal)) => {
-				tokens[i] = Token::Operator(SlashEqual);
-				tokens.remove(i+1);
-			}
-			/* combine + and = into += */
-			(Token::Operator(Plus), Token::Operator(Equal)) => {
-				tokens[i] = Token::Operator(PlusEqual);
-				tokens.remove(i+1);
-			}
-			/* combine - and = into -= */
-			(Token::Operator(Dash), Token::Operator(Equal)) => {
-				tokens[i] = Token::Operator(DashEqual);
-				tokens.remove(i+1);
-			}
-			_ => ()
+	while (i+3 < tokens.len()) {
+		if let (Token::Identifier(module), Token::Operator(Colon), Token::Operator(Colon), Token::Identifier(name)) = (&tokens[i], &tokens[i+1], &tokens[i+2], &tokens[i+3]) {
+			tokens[i] = Token::Identifier(format!("{module}::{name}"));
+			tokens.drain(i+1..i+4);
+
+			/* don't move on, so that paths with more than one :: get joined too */
+			continue;
 		}
 		i += 1;
 	}
@@ -260,6 +271,11 @@ fn push_token(mode: &LexerMode, token: &mut String, tokens: &mut Vec<Token>) ->
 		}
 		/* this deals with identifiers but also checks for keywords */
 		LexerMode::Identifier => {
+			/* the lexer starts out in identifier mode, so a file that starts with a comment would push an empty identifier */
+			if (token.is_empty()) {
+				return Ok(());
+			}
+
 			/* if its a keyword we push a keyword enum, if its not we just push a regular identifier */
 			let to_push = match Keyword::new(token) {
 				Some(x) => Token::Keyword(x),
@@ -274,7 +290,8 @@ fn push_token(mode: &LexerMode, token: &mut String, tokens: &mut Vec<Token>) ->
 	Ok(())
 }
 
-pub fn lex(input: &str) -> Result<Vec<Token>, String> {
+/* errors are (message, line, column), the column counts characters and not bytes, so it's right even with unicode in the line */
+pub fn lex(input: &str) -> Result<Vec<Token>, (String, i64, i64)> {
 	let mut iter = input.chars().peekable();
 
 	let mut tokens: Vec<Token> = Vec::new();
@@ -282,11 +299,21 @@ pub fn lex(input: &str) -> Result<Vec<Token>, String> {
 
 	let mut token = String::new();
 
+	/* where the character that's being lexed is, and where the token in 'token' started */
+	let (mut line, mut column) = (1, 0);
+	let mut start = (1, 1);
+
 	while let Some(i) = iter.next() {
+		column += 1;
+
 		/* -------------- */
 		/*    comments    */
 		/* -------------- */
-		let new_mode = if (i == '*') {
+		/* everything inside of a string literal is part of it, so "lib/io.cat" doesn't get lexed as an operator in the middle */
+		let new_mode = if (prev_mode == LexerMode::StringLiteral && i != '\"') {
+			LexerMode::StringLiteral
+		}
+		else if (i == '*') {
 			if let Some('/') = iter.peek()  {
 				iter.next(); /* skip '*' */
 				LexerMode::Ignore /* next token (/) gets just ignored */
@@ -323,6 +350,10 @@ pub fn lex(input: &str) -> Result<Vec<Token>, String> {
 		else if (prev_mode == LexerMode::StringLiteral) {
 			LexerMode::StringLiteral
 		}
+		/* unicode can be in string literals and comments, but not in code */
+		else if (!i.is_ascii()) {
+			return Err((format!("unexpected character '{i}', identifiers and operators can only have ascii characters in them"), line, column));
+		}
 		/* ----------------- */
 		/*    identifiers    */
 		/* ----------------- */
@@ -370,18 +401,32 @@ pub fn lex(input: &str) -> Result<Vec<Token>, String> {
 			prev_mode.clone()
 		};
 
+		/* multi character operators, like -> and ==, only if there's nothing in between the characters */
+		let joined = prev_mode == LexerMode::Operator && new_mode == LexerMode::Operator && Operator::new(&format!("{token}{i}")).is_some();
+
 		/* if we are now in a different state, push what everything we pushed into 'token' into the 'tokens' vector */
-		/* unless the state is Operator, we want to update tokens on every iteration if we're in the Operator state */
-		if (prev_mode != new_mode || prev_mode == LexerMode::Operator) {			
-			push_token(&prev_mode, &mut token, &mut tokens)?;			
+		/* unless the state is Operator or Newline, we want to update tokens on every iteration in those states */
+		/* (otherwise blank lines would get merged into one newline, and the line numbers after them would be wrong) */
+		if (!joined && (prev_mode != new_mode || prev_mode == LexerMode::Operator || prev_mode == LexerMode::Newline)) {
+			if let Err(err) = push_token(&prev_mode, &mut token, &mut tokens) {
+				return Err((err, start.0, start.1));
+			}
 			prev_mode = new_mode;
+			start = (line, column);
 		}
 
 		token.push(i);
+
+		if (i == '\n') {
+			line += 1;
+			column = 0;
+		}
 	}
 
 	/* we still need to add one more token that the iteration didnt go through*/
-	push_token(&prev_mode, &mut token, &mut tokens)?;
+	if let Err(err) = push_token(&prev_mode, &mut token, &mut tokens) {
+		return Err((err, start.0, start.1));
+	}
 	join_tokens(&mut tokens);
 
 	Ok(tokens)
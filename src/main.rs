@@ -7,6 +7,9 @@ mod options;
 mod lexer;
 mod parser;
 mod codegen;
+mod modules;
+mod preprocessor;
+mod cfg;
 
 #[macro_export]
 macro_rules! exit {
@@ -18,6 +21,13 @@ macro_rules! exit {
 	}
 }
 
+#[macro_export]
+macro_rules! warning {
+	($fmt:expr) => {
+		println!("{}", String::from("catlang: \x1b[33mwarning:\x1b[0m ") + &$fmt)
+	}
+}
+
 /* 
 	TODO:
 		rewrite the lexer
@@ -58,26 +68,12 @@ fn main() {
 		println!("{:?}", options);
 	}
 
-	/* --------------------------- */
-	/*    lex input into tokens    */
-	/* --------------------------- */
-	let tokens = match lexer::lex(&options.input) {
-		Ok(x) => x,
-		Err(err) => exit!(format!("{}", err))
-	};
-	if (options.verbose) {
-		println!("--------------------");
-		println!("       tokens       ");
-		println!("--------------------");
-		lexer::print_tokens(&tokens);
-	}
-
-	/* --------------------------- */
-	/*   generate AST from tokens  */
-	/* --------------------------- */
-	let ast = match parser::parse(tokens) {
+	/* ------------------------------------------------------------- */
+	/*   lex and parse the input file and every file it imports      */
+	/* ------------------------------------------------------------- */
+	let (ast, macro_notes) = match modules::load(&options.input, &options.cfg, options.verbose) {
 		Ok(x) => x,
-		Err((err, line)) => exit!(format!("[line {line}] {}", err))
+		Err(err) => exit!(err)
 	};
 	if (options.verbose) {
 		println!("------------------------");
@@ -91,13 +87,23 @@ fn main() {
 	/*   generate the assembly output from the AST   */
 	/* --------------------------------------------- */
 	let mut state = codegen::State::default();
+	state.panic = options.panic;
+	state.release = options.release;
+	codegen::define_operator_traits(&mut state);
 	if let Err((err, line)) = codegen::generate(&mut state, &ast) {
-		exit!(format!("[line {}] {}", (line+1), err))
+		exit!(format!("[{}, line {}] {}", state.file, (line+1), modules::with_macro_note(err, &macro_notes, &state.file, line+1)))
 	};
 
+	codegen::emit_runtime(&mut state);
+
+	for (message, file, line) in codegen::warnings(&state) {
+		warning!(format!("[{file}, line {}] {message}", line+1));
+	}
+
 	state.datasect.insert_str(0, "section .data\n");
+	state.rodata.insert_str(0, "section .rodata\n");
 	state.textsect.insert_str(0, "section .text\n\n");
-	let assembly_output = state.datasect + &state.textsect;
+	let assembly_output = state.datasect + &state.rodata + &state.textsect;
 
 	/* --------------------------------- */
 	/*  write assembly output to a file  */
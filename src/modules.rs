@@ -0,0 +1,245 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::lexer::{self, Token::{self, *}, Operator::*};
+use crate::parser::{self, AstType::{self, *}, Expression};
+use crate::preprocessor;
+use crate::cfg::{self, Cfg};
+
+/* this module loads the input file and every file that it imports (and every file that those import, and so on) */
+/* and combines all of them into a single AST, which gets compiled all at once */
+
+/* every imported file is its own module, named after the file, so "io.cat" becomes the module 'io' */
+/* the functions defined in a module get prefixed with its name, so 'write' in io.cat has to be called as io::write from other files */
+
+struct Loader {
+	verbose: bool,
+	cfg: Cfg,
+
+	/* files that have already been loaded, importing a file twice just does nothing */
+	loaded: HashSet<PathBuf>,
+	/* the chain of files that are currently being loaded, used to detect cyclic imports */
+	importing: Vec<PathBuf>,
+	/* module name -> the file it came from, so that two different files with the same name can't both get imported */
+	modules: HashMap<String, PathBuf>,
+
+	ast: Vec<AstType>,
+	notes: MacroNotes
+}
+
+/* (file, line) -> which macros were expanded on that line, see preprocessor.rs */
+pub type MacroNotes = HashMap<(String, i64), String>;
+
+/* adds the note about macro expansions on a line to an error message, if there were any */
+pub fn with_macro_note(err: String, notes: &MacroNotes, file: &str, line: i64) -> String {
+	match notes.get(&(file.to_owned(), line)) {
+		Some(note) => format!("{err} (in the expansion of {note})"),
+		None => err
+	}
+}
+
+/* qualifies a function name with the name of the module it's in, but only if it's one of the module's own functions */
+fn qualify_name(name: &str, module: &str, functions: &HashSet<String>) -> String {
+	if (functions.contains(name)) {
+		format!("{module}::{name}")
+	}
+	else {
+		name.to_owned()
+	}
+}
+
+fn qualify_expression(input: &Expression, module: &str, functions: &HashSet<String>) -> Expression {
+	let mut output = Vec::new();
+
+	for (i, v) in input.iter().enumerate() {
+		/* methods, like the len in v.len(), are looked up through the type of v instead */
+		if (i > 0 && matches!(input[i-1], Token::Operator(Dot))) {
+			output.push(v.clone());
+			continue;
+		}
+
+		match (v, input.get(i+1)) {
+			(Identifier(name), Some(Token::Operator(LeftParen))) => output.push(Identifier(qualify_name(name, module, functions))),
+			_ => output.push(v.clone())
+		}
+	}
+
+	output
+}
+
+/* prefixes every function that a module defines, and every call to those functions, with the name of the module */
+fn qualify_ast(input: Vec<AstType>, module: &str, functions: &HashSet<String>) -> Vec<AstType> {
+	input.into_iter().map(|x| match x {
+		FunctionDefinition(name, public, attributes, type_params, args, return_type, body) => FunctionDefinition(
+			qualify_name(&name, module, functions),
+			public,
+			attributes,
+			type_params,
+			args,
+			return_type,
+			qualify_ast(body, module, functions)
+		),
+		ReturnStatement(expr) => ReturnStatement(qualify_expression(&expr, module, functions)),
+		IfStatement(expr1, operator, expr2, body) => IfStatement(
+			qualify_expression(&expr1, module, functions),
+			operator,
+			qualify_expression(&expr2, module, functions),
+			qualify_ast(body, module, functions)
+		),
+		VariableDefinition(name, vartype, initexpr) => VariableDefinition(name, vartype, initexpr.map(|x| qualify_expression(&x, module, functions))),
+		TupleDestructuring(names, expr) => TupleDestructuring(names, qualify_expression(&expr, module, functions)),
+		VariableAssigment(name, expr) => VariableAssigment(name, qualify_expression(&expr, module, functions)),
+		MacroCall(name, args) => MacroCall(name, args.iter().map(|x| qualify_expression(x, module, functions)).collect()),
+		FunctionCall(name, args) => FunctionCall(
+			qualify_name(&name, module, functions),
+			args.iter().map(|x| qualify_expression(x, module, functions)).collect()
+		),
+		MethodCall(expr) => MethodCall(qualify_expression(&expr, module, functions)),
+		TryStatement(expr) => TryStatement(qualify_expression(&expr, module, functions)),
+		/* methods are named after their type instead of the module, so only the code inside of them gets qualified */
+		Impl(name, trait_name, body) => Impl(name, trait_name, body.into_iter().map(|x| match x {
+			FunctionDefinition(method, public, attributes, type_params, args, return_type, body) => {
+				FunctionDefinition(method, public, attributes, type_params, args, return_type, qualify_ast(body, module, functions))
+			}
+			_ => x
+		}).collect()),
+		_ => x
+	}).collect()
+}
+
+impl Loader {
+	fn load_file(&mut self, path: &Path, module: Option<&str>) -> Result<(), String> {
+		let canonical_path = match fs::canonicalize(path) {
+			Ok(x) => x,
+			Err(err) => return Err(format!("input file '{}' cannot be read [{err}]", path.display()))
+		};
+
+		if (self.importing.contains(&canonical_path)) {
+			let chain: Vec<String> = self.importing.iter().chain([&canonical_path]).map(|x| x.display().to_string()).collect();
+			return Err(format!("cyclic import, {}", chain.join(" -> ")));
+		}
+
+		if (self.loaded.contains(&canonical_path)) {
+			return Ok(());
+		}
+
+		let input = match fs::read_to_string(path) {
+			Ok(x) => x,
+			Err(err) => return Err(format!("input file '{}' cannot be read [{err}]", path.display()))
+		};
+
+		/* --------------------------- */
+		/*    lex input into tokens    */
+		/* --------------------------- */
+		let tokens = match lexer::lex(&input) {
+			Ok(x) => x,
+			Err((err, line, column)) => return Err(format!("[{}, line {line}, column {column}] {err}", path.display()))
+		};
+
+		/* take out whatever #[cfg(...)] says shouldn't be compiled, before macros so that they can be defined differently for different cfgs */
+		let tokens = match cfg::apply(tokens, &self.cfg) {
+			Ok(x) => x,
+			Err((err, line)) => return Err(format!("[{}, line {line}] {err}", path.display()))
+		};
+
+		/* expand user defined macros */
+		let (tokens, notes) = match preprocessor::preprocess(tokens) {
+			Ok(x) => x,
+			Err((err, line)) => return Err(format!("[{}, line {line}] {err}", path.display()))
+		};
+		for (line, note) in notes {
+			self.notes.insert((path.display().to_string(), line), note);
+		}
+		if (self.verbose) {
+			println!("--------------------");
+			println!("  tokens of {}", path.display());
+			println!("--------------------");
+			lexer::print_tokens(&tokens);
+		}
+
+		/* --------------------------- */
+		/*   generate AST from tokens  */
+		/* --------------------------- */
+		let mut ast = match parser::parse(tokens) {
+			Ok(x) => x,
+			Err((err, line)) => return Err(format!("[{}, line {line}] {}", path.display(), with_macro_note(err, &self.notes, &path.display().to_string(), line)))
+		};
+
+		/* ---------------------- */
+		/*    load the imports    */
+		/* ---------------------- */
+		/* imported modules get compiled before the module that imports them, because functions have to be defined before they can be called */
+		self.importing.push(canonical_path.clone());
+
+		for i in &ast {
+			let import = match i {
+				Import(x) => x,
+				_ => continue
+			};
+
+			let import_path = match path.parent() {
+				Some(x) => x.join(import),
+				None => PathBuf::from(import)
+			};
+
+			let module_name = match import_path.file_stem() {
+				Some(x) => x.to_string_lossy().to_string(),
+				None => return Err(format!("[{}] cannot import '{import}', it is not a file", path.display()))
+			};
+
+			if (!module_name.chars().all(|x| x.is_ascii_alphanumeric() || x == '_')) {
+				return Err(format!("[{}] cannot import '{import}', '{module_name}' is not a valid module name, the names of imported files can only have letters, numbers and underscores in them", path.display()));
+			}
+
+			let canonical_import = match fs::canonicalize(&import_path) {
+				Ok(x) => x,
+				Err(err) => return Err(format!("[{}] cannot import '{import}' [{err}]", path.display()))
+			};
+
+			match self.modules.get(&module_name) {
+				Some(existing) if existing != &canonical_import => {
+					return Err(format!("[{}] cannot import '{import}', a different module named '{module_name}' ({}) has already been imported", path.display(), existing.display()));
+				}
+				_ => self.modules.insert(module_name.clone(), canonical_import)
+			};
+
+			self.load_file(&import_path, Some(&module_name))?;
+		}
+
+		self.importing.pop();
+
+		if let Some(module) = module {
+			let functions: HashSet<String> = ast.iter().filter_map(|x| match x {
+				FunctionDefinition(name, ..) => Some(name.clone()),
+				_ => None
+			}).collect();
+
+			ast = qualify_ast(ast, module, &functions);
+		}
+
+		self.loaded.insert(canonical_path);
+
+		self.ast.push(SourceFile(path.display().to_string()));
+		self.ast.append(&mut ast);
+
+		Ok(())
+	}
+}
+
+/* loads the input file and everything it imports into a single AST */
+pub fn load(path: &str, cfg: &Cfg, verbose: bool) -> Result<(Vec<AstType>, MacroNotes), String> {
+	let mut loader = Loader {
+		verbose,
+		cfg: cfg.clone(),
+		loaded: HashSet::new(),
+		importing: Vec::new(),
+		modules: HashMap::new(),
+		ast: Vec::new(),
+		notes: HashMap::new()
+	};
+
+	loader.load_file(Path::new(path), None)?;
+
+	Ok((loader.ast, loader.notes))
+}
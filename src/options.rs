@@ -1,13 +1,28 @@
-use std::fs;
 use std::env;
 
+use crate::cfg::Cfg;
+
+/* what panic! and failed assert!s do after printing their message */
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum PanicStrategy {
+	/* exit with status 101 */
+	#[default]
+	Abort,
+	/* execute ud2, which raises SIGILL, so that a debugger stops right where it happened */
+	Trap
+}
+
 #[derive(Debug, Default)]
 pub struct Options {
 	pub input: String,
 	pub output_name: Option<String>,
 	pub create_binary: bool,
 	pub link_libc: bool,
-	pub verbose: bool
+	pub verbose: bool,
+	pub panic: PanicStrategy,
+	pub release: bool,
+	/* the names that #[cfg(...)] and cfg!(...) can check for, from -D and the ones that get defined by default */
+	pub cfg: Cfg
 }
 
 fn print_help() {
@@ -20,6 +35,9 @@ fn print_help() {
 	println!("-lc, --link-libc - when creating a binary, automatically link libc with gcc [must have gcc installed]");
 	println!("-V, --verbose - makes the compiler print some information about what it's doing");
 	println!("-o, --output-name - set the filename of the output file/binary");
+	println!("--release - disable dbg!, it still evaluates to the value of its argument but doesn't print anything");
+	println!("--panic=abort|trap - what panic! and assert! do when they fail, exit with status 101 (the default) or execute ud2");
+	println!("-D NAME[=VALUE] - define a name for #[cfg(...)] and cfg!(...) to check, libc or freestanding, debug (without --release), target_arch and target_os are always defined");
 	
 	std::process::exit(0);
 }
@@ -51,21 +69,44 @@ pub fn get_options() -> Result<Options, String> {
 		else if (i == "-o" || i == "--output-name") {
 			options.output_name = args.next();
 		}
+		else if (i == "--release") {
+			options.release = true;
+		}
+		else if let Some(define) = i.strip_prefix("-D") {
+			/* both -D NAME and -DNAME work */
+			let define = match define {
+				"" => match args.next() {
+					Some(x) => x,
+					None => return Err(String::from("expected a name after -D, like -D NAME or -D NAME=VALUE"))
+				},
+				_ => define.to_owned()
+			};
+
+			let (name, value) = match define.split_once('=') {
+				Some((name, value)) => (name.to_owned(), Some(value.to_owned())),
+				None => (define, None)
+			};
+
+			if (name.is_empty() || !name.chars().all(|x| x.is_ascii_alphanumeric() || x == '_')) {
+				return Err(format!("invalid name '{name}' in -D, names can only have letters, numbers and underscores in them"));
+			}
+
+			options.cfg.insert(name, value);
+		}
+		else if let Some(strategy) = i.strip_prefix("--panic=") {
+			options.panic = match strategy {
+				"abort" => PanicStrategy::Abort,
+				"trap" => PanicStrategy::Trap,
+				_ => return Err(format!("invalid panic strategy '{strategy}', expected 'abort' or 'trap'"))
+			};
+		}
 		else if (!i.starts_with('-')) {
 			if (!options.input.is_empty()) {
 				return Err(String::from("more than one input file"));
 			}
-			
-			let input = match(fs::read_to_string(&i)) {
-				Ok(x) => x,
-				Err(err) => return Err(format!("input file '{}' cannot be read [{err}]", i))
-			};
-
-			if (!input.is_ascii()) {
-				return Err(String::from("input file is not in ascii, please remove any unicode symbols"));
-			}
 
-			options.input = input;
+			/* the file gets read (along with everything it imports) by modules::load */
+			options.input = i;
 		}
 		else {
 			return Err(format!("invalid option '{}'", i));
@@ -80,5 +121,27 @@ pub fn get_options() -> Result<Options, String> {
 		return Err(String::from("no input file"));
 	}
 
+	/* these are decided by the options, so they can't be changed with -D */
+	for i in ["libc", "freestanding", "debug", "target_arch", "target_os"] {
+		if (options.cfg.contains_key(i)) {
+			return Err(format!("'{i}' cannot be defined with -D, the compiler defines it by itself"));
+		}
+	}
+
+	if (options.link_libc) {
+		options.cfg.insert(String::from("libc"), None);
+	}
+	else {
+		options.cfg.insert(String::from("freestanding"), None);
+	}
+
+	if (!options.release) {
+		options.cfg.insert(String::from("debug"), None);
+	}
+
+	/* catlang only targets x86_64 linux for now */
+	options.cfg.insert(String::from("target_arch"), Some(String::from("x86_64")));
+	options.cfg.insert(String::from("target_os"), Some(String::from("linux")));
+
 	Ok(options)
 }
\ No newline at end of file
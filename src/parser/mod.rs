@@ -1,14 +1,15 @@
 use crate::lexer::Token::{self, *};
 use crate::lexer::{Operator::{self, *}, Keyword};
-use crate::exit;
 
 pub type Expression = Vec<Token>;
 pub type BlockStatement = Vec<AstType>;
+/* the name of a type paramater, and the traits that the type it gets bound to has to implement, like T in 'fn f<T: Writer>(w: T)' */
+pub type TypeParam = (String, Vec<String>);
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum AstType {
-	/* function name, tuple of vectors, first vector holds names, second one holds types, return type, body */
-	FunctionDefinition(String, (Vec<String>, Vec<String>), Option<String>, BlockStatement),
+	/* function name, whether it's pub, attributes, generic type paramaters (with the traits they have to implement), tuple of vectors, first vector holds names, second one holds types, return type, body */
+	FunctionDefinition(String, bool, Vec<Attribute>, Vec<TypeParam>, (Vec<String>, Vec<String>), Option<String>, BlockStatement),
 	/* function name, vector of types that the function accepts, return type */
 	FunctionPrototype(String, Vec<String>, Option<String>),
 	/* expression */
@@ -17,6 +18,9 @@ pub enum AstType {
 	IfStatement(Expression, ComparisonOperator, Expression, BlockStatement),
 	/* variable name, type, and initializer value */
 	VariableDefinition(String, Option<String>, Option<Expression>),
+	/* destructuring a tuple into new variables, like 'let (a, b) = f()' */
+	/* variable names, initializer value */
+	TupleDestructuring(Vec<String>, Expression),
 	/* assigning a value to an already existing variable, like 'num = 5' */
 	/* variable name, assignment expression */
 	VariableAssigment(String, Expression),
@@ -24,11 +28,30 @@ pub enum AstType {
 	MacroCall(String, Vec<Expression>),
 	/* function name, arguments */
 	FunctionCall(String, Vec<Expression>),
+	/* path to the imported file, relative to the file that imports it */
+	Import(String),
+	/* a name for another type, like 'type Fd = i32' */
+	/* name, the type it stands for, and whether it's a newtype (which can't be mixed with the type it stands for) */
+	TypeAlias(String, String, bool),
+	/* the methods of a type, like 'impl Vec3 { fn len(self) -> i64 { ... } }' or 'impl Writer for File { ... }' */
+	/* name of the type, the trait that it implements, the functions in the impl block */
+	Impl(String, Option<String>, BlockStatement),
+	/* a set of methods that types can implement, like 'trait Writer { fn write(self, buf: i64, n: i64) -> i64 }' */
+	/* name of the trait, the prototypes of its methods */
+	Trait(String, BlockStatement),
+	/* a method call whose return value isn't used, like 'v.print()' */
+	/* the whole expression, starting with the receiver */
+	MethodCall(Expression),
+	/* a call that returns a Result, which gets checked with ? but isn't used otherwise, like 'write(fd, buf)?' */
+	/* the whole expression, including the ? */
+	TryStatement(Expression),
+	/* marks where the code of a file starts, so that the codegen knows which file it's in (see modules.rs) */
+	SourceFile(String),
 	/* for counting the line number in parser.rs */
 	Newline
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum ComparisonOperator {
 	Equal, // == 
 	NotEqual, // !=
@@ -38,6 +61,23 @@ pub enum ComparisonOperator {
 	LessThanEqual // <= 
 }
 
+/* attributes that can be put on functions, like #[inline] or #[section(".text.hot")] */
+#[derive(Debug, Clone, PartialEq)]
+pub enum Attribute {
+	/* the body of the function gets inserted wherever it's called, instead of calling it */
+	Inline,
+	/* the function never returns, so it doesn't get an epilogue */
+	NoReturn,
+	/* the function is rarely called, so it gets put in .text.unlikely */
+	Cold,
+	/* puts the function in a specific section */
+	Section(String),
+	/* aligns the start of the function to a number of bytes */
+	Align(i64),
+	/* the function doesn't get a prologue or an epilogue, its body can only be asm! */
+	Naked
+}
+
 pub fn print_ast(ast: &[AstType], indent_levels: u64) {
 	for i in ast {
 		for _ in 0..indent_levels {
@@ -45,8 +85,8 @@ pub fn print_ast(ast: &[AstType], indent_levels: u64) {
 		}
 
 		match (i) {
-			AstType::FunctionDefinition(name, (arg_names, arg_types), return_type, body) => {
-				print!("FunctionDefintion(name: {name}, arg_names: {:?}, arg_types: {:?}, return_type: {:?}) {{", arg_names, arg_types, return_type);
+			AstType::FunctionDefinition(name, public, attributes, type_params, (arg_names, arg_types), return_type, body) => {
+				print!("FunctionDefintion(name: {name}, pub: {public}, attributes: {:?}, type_params: {:?}, arg_names: {:?}, arg_types: {:?}, return_type: {:?}) {{", attributes, type_params, arg_names, arg_types, return_type);
 				print_ast(body, 1);
 
 				print!("}}");			}
@@ -72,20 +112,28 @@ pub fn print_ast(ast: &[AstType], indent_levels: u64) {
 fn seperate_expression(iter: &mut core::slice::Iter<Token>, terminator: &Operator) -> Expression {
 	let mut expression: Expression = Vec::new();
 
-	for i in iter.by_ref() {
+	while let Some(i) = iter.clone().next() {
 		match i {
 			/* if we stumble upon a newline and the input was Semicolon, that means we should stop */
+			/* the newline doesn't get consumed, so that the line number still gets incremented for it */
 			Newline if terminator == &Semicolon => break,
-			Operator(x) if x == terminator => break,
+			Operator(x) if x == terminator => {
+				iter.next();
+				break;
+			}
 			
-			_ => expression.push(i.clone())
+			_ => {
+				iter.next();
+				expression.push(i.clone())
+			}
 		}
 	}
 
 	expression
 }
 
-fn seperate_block_statement(iter: &mut core::slice::Iter<Token>, line: i64) -> BlockStatement {
+/* 'line' is the line that the block starts on, it gets incremented for every line in the block */
+fn seperate_block_statement(iter: &mut core::slice::Iter<Token>, line: &mut i64) -> Result<BlockStatement, (String, i64)> {
 	let mut block_statement_tokens = Vec::new();
 	let mut scopes: u64 = 1; /* if this is initialized to 0 we will overflow */
 
@@ -108,10 +156,129 @@ fn seperate_block_statement(iter: &mut core::slice::Iter<Token>, line: i64) -> B
 		}
 	}
 
-	/* we return here if we parsed the tokens successfully */
+	let start = *line;
+	*line += block_statement_tokens.iter().filter(|x| matches!(x, Newline)).count() as i64;
+
+	/* the block gets parsed on its own, so its line numbers start at 1 on the line that the block starts on */
 	match parse(block_statement_tokens) {
-		Ok(x) => x,
-		Err((err, errline)) => exit!(format!("[line {}] {}", (line+1)+(errline+1)+2, err))
+		Ok(x) => Ok(x),
+		Err((err, errline)) => Err((err, start + errline - 1))
+	}
+}
+
+/* returns the comparison operator that a token is, if it is one */
+pub fn comparison_operator(token: &Token) -> Option<ComparisonOperator> {
+	match token {
+		Operator(DoubleEqual) => Some(ComparisonOperator::Equal), // ==
+		Operator(BangEqual) => Some(ComparisonOperator::NotEqual), // !=
+		Operator(RightAngle) => Some(ComparisonOperator::GreaterThan), // >
+		Operator(RightAngleEqual) => Some(ComparisonOperator::GreaterThanEqual), // >=
+		Operator(LeftAngle) => Some(ComparisonOperator::LessThan), // <
+		Operator(LeftAngleEqual) => Some(ComparisonOperator::LessThanEqual), // <=
+		_ => None
+	}
+}
+
+/* parses the attributes inside of #[...] and adds them to 'attributes', the # has already been consumed */
+/* multiple attributes can be put in the same brackets, like #[inline, section(".text.hot")] */
+fn parse_attributes(iter: &mut core::slice::Iter<Token>, attributes: &mut Vec<Attribute>, line: i64) -> Result<(), (String, i64)> {
+	if (!matches!(iter.next(), Some(Operator(LeftSquare)))) {
+		return Err(("expected '[' after '#'".to_owned(), line));
+	}
+
+	loop {
+		let name = match iter.next() {
+			Some(Identifier(x)) => x,
+			Some(x) => return Err((format!("expected the name of an attribute, but got {x} instead"), line)),
+			None => return Err(("expected the name of an attribute".to_owned(), line))
+		};
+
+		let attribute = match name.as_str() {
+			"inline" => Attribute::Inline,
+			"noreturn" => Attribute::NoReturn,
+			"cold" => Attribute::Cold,
+			"naked" => Attribute::Naked,
+			"section" => match (iter.next(), iter.next(), iter.next()) {
+				(Some(Operator(LeftParen)), Some(StringLiteral(x)), Some(Operator(RightParen))) => Attribute::Section(x.clone()),
+				_ => return Err(("expected the name of a section as a string literal in parentheses after section, like section(\".text.hot\")".to_owned(), line))
+			},
+			"align" => match (iter.next(), iter.next(), iter.next()) {
+				(Some(Operator(LeftParen)), Some(Numerical(x)), Some(Operator(RightParen))) => match x.parse::<i64>() {
+					Ok(x) if x > 0 && (x & (x-1)) == 0 => Attribute::Align(x),
+					_ => return Err((format!("the alignment of a function has to be a power of 2, but got {x}"), line))
+				},
+				_ => return Err(("expected a number in parentheses after align, like align(16)".to_owned(), line))
+			},
+			"cfg" => return Err(("cfg has to be in its own #[...], like #[cfg(libc)]".to_owned(), line)),
+			_ => return Err((format!("unknown attribute '{name}', expected inline, noreturn, cold, section, align, naked or cfg"), line))
+		};
+
+		if (attributes.contains(&attribute)) {
+			return Err((format!("attribute '{name}' is used more than once"), line));
+		}
+		attributes.push(attribute);
+
+		match iter.next() {
+			Some(Operator(Comma)) => (),
+			Some(Operator(RightSquare)) => break,
+			_ => return Err((format!("expected ',' or ']' after attribute '{name}'"), line))
+		}
+	}
+
+	Ok(())
+}
+
+/* parses a type, which is either a single identifier like 'i32', a tuple of types like '(i64, i32)', a slice like '[i8]' or a Result like 'Result<i64, i32>' */
+/* types get passed around as strings until the codegen turns them into DataTypes, so a tuple type is returned as the string "(i64, i32)" */
+pub fn parse_type(iter: &mut core::slice::Iter<Token>) -> Option<String> {
+	match iter.next() {
+		/* trait objects, like dyn Writer */
+		Some(Identifier(x)) if x == "dyn" => match iter.next() {
+			Some(Identifier(x)) => Some(format!("dyn {x}")),
+			_ => None
+		},
+		/* results, like Result<i64, i32> */
+		Some(Identifier(x)) if x == "Result" && matches!(iter.clone().next(), Some(Operator(LeftAngle))) => {
+			iter.next(); /* skip < */
+			let ok = parse_type(iter)?;
+
+			if (!matches!(iter.next(), Some(Operator(Comma)))) {
+				return None;
+			}
+
+			let err = parse_type(iter)?;
+
+			match iter.next() {
+				Some(Operator(RightAngle)) => Some(format!("Result<{ok}, {err}>")),
+				_ => None
+			}
+		}
+		Some(Identifier(x)) => Some(x.to_owned()),
+		Some(Operator(LeftParen)) => {
+			let mut members: Vec<String> = Vec::new();
+
+			loop {
+				members.push(parse_type(iter)?);
+
+				match iter.next() {
+					Some(Operator(Comma)) => (),
+					Some(Operator(RightParen)) => break,
+					_ => return None
+				}
+			}
+
+			Some(format!("({})", members.join(", ")))
+		}
+		/* slices, like [i64] */
+		Some(Operator(LeftSquare)) => {
+			let element = parse_type(iter)?;
+
+			match iter.next() {
+				Some(Operator(RightSquare)) => Some(format!("[{element}]")),
+				_ => None
+			}
+		}
+		_ => None
 	}
 }
 
@@ -124,8 +291,16 @@ pub fn process_function_parameters(iter: &mut core::slice::Iter<Token>) -> Vec<E
 
 	/* iterate over tokens and push the arguments to 'arguments' vector */
 	'outer: while let Some(v) = iter.next() {
-		if let Operator(RightParen) = v {
-			break;
+		match v {
+			Operator(RightParen) => break,
+			/* arguments can be on their own lines */
+			Newline => continue,
+			_ => ()
+		}
+
+		/* arguments that start with a parenthesis, like tuple expressions */
+		if let Operator(LeftParen) = v {
+			function_levels += 1;
 		}
 
 		let mut expr = Vec::new();
@@ -170,9 +345,16 @@ pub fn process_function_parameters(iter: &mut core::slice::Iter<Token>) -> Vec<E
 pub fn parse(input: Vec<Token>) -> Result<Vec<AstType>, (String, i64)> {
 	let mut ast: Vec<AstType> = Vec::new();
 	let mut line: i64 = 1;
+	/* set by the pub keyword and attributes, for the function that comes after them */
+	let mut public = false;
+	let mut attributes: Vec<Attribute> = Vec::new();
 
 	let mut iter = input.iter();
 	while let Some(i) = iter.next() {
+		if (!attributes.is_empty() && !matches!(i, Newline | Operator(Hash) | Keyword(Keyword::Pub) | Keyword(Keyword::Fn))) {
+			return Err((format!("attributes can only be put on functions, but got {i} after them"), line));
+		}
+
 		match i {
 			/* increment line number on newline token */
 			Newline => {
@@ -182,15 +364,69 @@ pub fn parse(input: Vec<Token>) -> Result<Vec<AstType>, (String, i64)> {
 			/* ------------------------------------- */
 			/*    function definitions/prototypes    */
 			/* ------------------------------------- */
+			/* pub makes a function callable from other files, and exports it when linking */
+			Keyword(Keyword::Pub) => {
+				match iter.clone().next() {
+					Some(Keyword(Keyword::Fn)) => public = true,
+					Some(x) => return Err((format!("expected 'fn' after pub, but got {x} instead"), line)),
+					None => return Err(("expected 'fn' after pub".to_owned(), line))
+				}
+			},
+			Operator(Hash) => parse_attributes(&mut iter, &mut attributes, line)?,
 			Keyword(Keyword::Fn) => {
+				let public = std::mem::take(&mut public);
+				let attributes = std::mem::take(&mut attributes);
+
 				let function_name = match iter.next() {
 					Some(Identifier(x)) => x,
 					_ => return Err(("expected identifier after function keyword".to_owned(), line))
 				};
 
+				/* generic type paramaters, like the T in 'fn max<T>(a: T, b: T) -> T' */
+				let mut type_params: Vec<TypeParam> = Vec::new();
+
 				/* check for ( */
 				match iter.next() {
 					Some(Operator(LeftParen)) => (),
+					Some(Operator(LeftAngle)) => {
+						loop {
+							let name = match iter.next() {
+								Some(Identifier(x)) => x.to_owned(),
+								_ => return Err((format!("expected a type paramater name in the type paramater list of {function_name}"), line))
+							};
+
+							/* trait bounds, like T: Writer + Reader */
+							let mut bounds: Vec<String> = Vec::new();
+							if let Some(Operator(Colon)) = iter.clone().next() {
+								iter.next();
+
+								loop {
+									match iter.next() {
+										Some(Identifier(x)) => bounds.push(x.to_owned()),
+										_ => return Err((format!("expected the name of a trait after '{name}:' in the type paramater list of {function_name}"), line))
+									}
+
+									match iter.clone().next() {
+										Some(Operator(Plus)) => iter.next(),
+										_ => break
+									};
+								}
+							}
+
+							type_params.push((name, bounds));
+
+							match iter.next() {
+								Some(Operator(Comma)) => (),
+								Some(Operator(RightAngle)) => break,
+								_ => return Err((format!("expected ',' or '>' after type paramater in function definition of {function_name}"), line))
+							}
+						}
+
+						match iter.next() {
+							Some(Operator(LeftParen)) => (),
+							_ => return Err((format!("in function definition of {function_name}, expected '(' after the type paramater list"), line))
+						}
+					}
 					_ => return Err((format!("in function definition of {function_name}, expected '(' after the function name"), line))
 				}
 
@@ -200,6 +436,15 @@ pub fn parse(input: Vec<Token>) -> Result<Vec<AstType>, (String, i64)> {
 
 				while let Some(i) = iter.next() {
 					match i {
+						/* a method can just take 'self', which is short for 'self: Self' (see codegen/methods.rs) */
+						Identifier(varname) if varname == "self" && matches!(iter.clone().next(), Some(Operator(Comma)) | Some(Operator(RightParen))) => {
+							arg_names.push(varname.to_owned());
+							arg_types.push("Self".to_owned());
+
+							if let Some(Operator(RightParen)) = iter.next() {
+								break;
+							}
+						}
 						Identifier(varname) => {
 							match iter.next() {
 								Some(Operator(Colon)) => (),
@@ -207,8 +452,8 @@ pub fn parse(input: Vec<Token>) -> Result<Vec<AstType>, (String, i64)> {
 							}
 
 							arg_names.push(varname.to_owned()); 
-							arg_types.push(match iter.next() {
-								Some(Identifier(vartype)) => vartype.to_owned(),
+							arg_types.push(match parse_type(&mut iter) {
+								Some(vartype) => vartype,
 								_ => return Err((format!("expected a type after paramater name '{varname}' in function decleration of {function_name}"), line))
 							});
 
@@ -228,40 +473,162 @@ pub fn parse(input: Vec<Token>) -> Result<Vec<AstType>, (String, i64)> {
 
 				let mut return_type: Option<String> = None;
 				let mut is_proto = false;
+				/* prototypes can end with a newline, which still has to be counted */
+				let mut proto_newline = false;
 
 				/* determine return type */
 				match iter.next() {
 					Some(Operator(Arrow)) => {
 						/* get the actual return type */
-						return_type = match iter.next() {
-							Some(Identifier(x)) => Some(x.to_owned()),
+						return_type = match parse_type(&mut iter) {
+							Some(x) => Some(x),
 
 							_ => return Err((format!("expected return type after '->' in function prototype of '{function_name}'"), line))
 						};
 
 						match iter.next() {
 							Some(Operator(LeftCurly)) => (),
-							Some(Operator(Semicolon)) | Some(Newline) => is_proto = true,
+							Some(Operator(Semicolon)) => is_proto = true,
+							Some(Newline) => (is_proto, proto_newline) = (true, true),
 
 							/* unwrap will never panic here */
 							_ => return Err((format!("expected '{{' after '-> {}', or a ';'/newline if this is a function prototype", return_type.unwrap()), line))
 						}
 					}
 					Some(Operator(LeftCurly)) => (),
-					Some(Operator(Semicolon)) | Some(Newline) => is_proto = true,
+					Some(Operator(Semicolon)) => is_proto = true,
+					Some(Newline) => (is_proto, proto_newline) = (true, true),
 					_ => return Err((format!("expected either '{{', '->', ';' or a newline after the paramater list of '{function_name}'"), line))
 				}
 
 				if (is_proto) {
+					if (!type_params.is_empty()) {
+						return Err((format!("function prototype of {function_name} cannot have type paramaters, only functions with a body can be generic"), line));
+					}
+
+					if (!attributes.is_empty()) {
+						return Err((format!("function prototype of {function_name} cannot have attributes, only functions with a body can"), line));
+					}
+
+					/* prototypes refer to functions defined somewhere else, so they can't be exported */
+					if (public) {
+						return Err((format!("function prototype of {function_name} cannot be pub, only functions with a body can be"), line));
+					}
+
 					ast.push(AstType::FunctionPrototype(function_name.to_owned(), arg_types, return_type));
+
+					if (proto_newline) {
+						line += 1;
+						ast.push(AstType::Newline);
+					}
 					continue;
 				}
 				
-				let block_statement = seperate_block_statement(&mut iter, line);
+				let block_statement = seperate_block_statement(&mut iter, &mut line)?;
 				println!("block statement of {}: {:?}", function_name, block_statement);
 				
-				ast.push(AstType::FunctionDefinition(function_name.to_owned(), (arg_names, arg_types), return_type, block_statement));
+				ast.push(AstType::FunctionDefinition(function_name.to_owned(), public, attributes, type_params, (arg_names, arg_types), return_type, block_statement));
 			},
+			/* ------------- */
+			/*    imports    */
+			/* ------------- */
+			Keyword(Keyword::Import) => {
+				let path = match iter.next() {
+					Some(StringLiteral(x)) => x,
+					_ => return Err(("expected the path of a file as a string literal after import keyword".to_owned(), line))
+				};
+
+				ast.push(AstType::Import(path.to_owned()));
+
+				match iter.next() {
+					Some(Operator(Semicolon)) | None => (),
+					Some(Newline) => {
+						line += 1;
+						ast.push(AstType::Newline);
+					}
+					_ => return Err((format!("expected ';' or newline after import \"{path}\""), line))
+				}
+			}
+			/* ------------------ */
+			/*    type aliases    */
+			/* ------------------ */
+			Keyword(x @ (Keyword::Type | Keyword::Newtype)) => {
+				let newtype = matches!(x, Keyword::Newtype);
+				let keyword = if (newtype) {
+					"newtype"
+				}
+				else {
+					"type"
+				};
+
+				let name = match iter.next() {
+					Some(Identifier(x)) => x,
+					_ => return Err((format!("expected the name of the type after {keyword} keyword, like '{keyword} Fd = i32'"), line))
+				};
+
+				if (!matches!(iter.next(), Some(Operator(Equal)))) {
+					return Err((format!("expected '=' after the name of type {name}"), line));
+				}
+
+				let aliased = match parse_type(&mut iter) {
+					Some(x) => x,
+					None => return Err((format!("expected a type after '=' in the definition of type {name}"), line))
+				};
+
+				ast.push(AstType::TypeAlias(name.to_owned(), aliased, newtype));
+
+				match iter.next() {
+					Some(Operator(Semicolon)) | None => (),
+					Some(Newline) => {
+						line += 1;
+						ast.push(AstType::Newline);
+					}
+					_ => return Err((format!("expected ';' or newline after the definition of type {name}"), line))
+				}
+			}
+			/* ---------------------------- */
+			/*    impl blocks and traits    */
+			/* ---------------------------- */
+			Keyword(Keyword::Impl) => {
+				let name = match parse_type(&mut iter) {
+					Some(x) => x,
+					None => return Err(("expected the name of a type after impl keyword, like 'impl Vec3 { ... }'".to_owned(), line))
+				};
+
+				/* 'impl Writer for File', where the name we just got is actually the trait */
+				let (name, trait_name) = match iter.next() {
+					Some(Operator(LeftCurly)) => (name, None),
+					Some(Identifier(x)) if x == "for" => {
+						let type_name = match parse_type(&mut iter) {
+							Some(x) => x,
+							None => return Err((format!("expected the name of a type after 'impl {name} for'"), line))
+						};
+
+						if (!matches!(iter.next(), Some(Operator(LeftCurly)))) {
+							return Err((format!("expected '{{' after 'impl {name} for {type_name}'"), line));
+						}
+
+						(type_name, Some(name))
+					}
+					_ => return Err((format!("expected '{{' or 'for' after 'impl {name}'"), line))
+				};
+
+				let block_statement = seperate_block_statement(&mut iter, &mut line)?;
+				ast.push(AstType::Impl(name, trait_name, block_statement));
+			}
+			Keyword(Keyword::Trait) => {
+				let name = match iter.next() {
+					Some(Identifier(x)) => x.to_owned(),
+					_ => return Err(("expected the name of a trait after trait keyword, like 'trait Writer { ... }'".to_owned(), line))
+				};
+
+				if (!matches!(iter.next(), Some(Operator(LeftCurly)))) {
+					return Err((format!("expected '{{' after 'trait {name}'"), line));
+				}
+
+				let block_statement = seperate_block_statement(&mut iter, &mut line)?;
+				ast.push(AstType::Trait(name, block_statement));
+			}
 			/* ------------------------ */
 			/*    function returning    */
 			/* ------------------------ */
@@ -284,39 +651,12 @@ pub fn parse(input: Vec<Token>) -> Result<Vec<AstType>, (String, i64)> {
 				let mut operator: Option<ComparisonOperator> = None;
 
 				for i in iter.by_ref() {
-					match i {
-						/* == */
-						Operator(DoubleEqual) => {
-							operator = Some(ComparisonOperator::Equal);
-							break;
-						},
-						/* != */
-						Operator(BangEqual) => {
-							operator = Some(ComparisonOperator::NotEqual);
-							break;
-						}
-						/* > */
-						Operator(RightAngle) => {
-							operator = Some(ComparisonOperator::GreaterThan);
-							break;
-						}
-						/* >= */
-						Operator(RightAngleEqual) => {
-							operator = Some(ComparisonOperator::GreaterThanEqual);
-							break;
-						}
-						/* < */
-						Operator(LeftAngle) => {
-							operator = Some(ComparisonOperator::LessThan);
-							break;
-						}
-						/* <= */
-						Operator(LeftAngleEqual) => {
-							operator = Some(ComparisonOperator::LessThanEqual);
-							break;
-						}
-						_ => expr1.push(i.clone())
+					if let Some(x) = comparison_operator(i) {
+						operator = Some(x);
+						break;
 					}
+
+					expr1.push(i.clone());
 				}
 
 				let operator = match operator {
@@ -325,14 +665,15 @@ pub fn parse(input: Vec<Token>) -> Result<Vec<AstType>, (String, i64)> {
 				};
 
 				let mut expr2 = seperate_expression(&mut iter, &LeftCurly);
-				let block_statement = seperate_block_statement(&mut iter, line);
+				let start = line;
+				let block_statement = seperate_block_statement(&mut iter, &mut line)?;
 
 				/* the last element of the expression will be ), which we do not want so we get rid of it */
 				match expr2.last() {
 					Some(Operator(RightParen)) => expr2.pop(),
 
-					Some(x) => return Err((format!("expected ')' before '{{' in if statement, but got {x}"), line)),
-					_ => return Err((String::from("expected ')' before '{{' in if statement"), line))
+					Some(x) => return Err((format!("expected ')' before '{{' in if statement, but got {x}"), start)),
+					_ => return Err((String::from("expected ')' before '{{' in if statement"), start))
 				};
 
 				ast.push(AstType::IfStatement(expr1, operator, expr2, block_statement));
@@ -344,6 +685,33 @@ pub fn parse(input: Vec<Token>) -> Result<Vec<AstType>, (String, i64)> {
 				/* get variable name */
 				let variable_name = match iter.next() {
 					Some(Identifier(x)) => x,
+					/* tuple destructuring, like 'let (a, b) = f()' */
+					Some(Operator(LeftParen)) => {
+						let mut names: Vec<String> = Vec::new();
+
+						loop {
+							match iter.next() {
+								Some(Identifier(x)) => names.push(x.to_owned()),
+								_ => return Err(("expected identifier in tuple destructuring".to_owned(), line))
+							}
+
+							match iter.next() {
+								Some(Operator(Comma)) => (),
+								Some(Operator(RightParen)) => break,
+								_ => return Err(("expected ',' or ')' after identifier in tuple destructuring".to_owned(), line))
+							}
+						}
+
+						match iter.next() {
+							Some(Operator(Equal)) => (),
+							_ => return Err(("expected '=' after tuple destructuring, destructured variables must be initialized".to_owned(), line))
+						}
+
+						let initexpr = seperate_expression(&mut iter, &Semicolon);
+						ast.push(AstType::TupleDestructuring(names, initexpr));
+
+						continue;
+					}
 					_ => return Err(("expected identifier after let keyword".to_owned(), line))
 				};
 
@@ -361,18 +729,24 @@ pub fn parse(input: Vec<Token>) -> Result<Vec<AstType>, (String, i64)> {
 				};
 				
 				/* get variable type */
-				let variable_type = match iter.next() {
-					Some(Identifier(x)) => x,
-					_ => return Err(("expected identifier after ','".to_owned(), line))
+				let variable_type = match parse_type(&mut iter) {
+					Some(x) => x,
+					_ => return Err(("expected a type after ':'".to_owned(), line))
 				};
 
 				/* check if there's a = after the type name */
 				match iter.next() {
 					Some(Operator(Equal)) => (),
-					Some(Operator(Semicolon) | Newline) => {
+					Some(Operator(Semicolon)) => {
 						ast.push(AstType::VariableDefinition(variable_name.to_owned(), Some(variable_type.to_owned()), None));
 						continue;
 					}
+					Some(Newline) => {
+						ast.push(AstType::VariableDefinition(variable_name.to_owned(), Some(variable_type.to_owned()), None));
+						line += 1;
+						ast.push(AstType::Newline);
+						continue;
+					}
 					_ => return Err(("expected '=', ';' or newline after type name".to_owned(), line))
 				};
 
@@ -388,20 +762,48 @@ pub fn parse(input: Vec<Token>) -> Result<Vec<AstType>, (String, i64)> {
 			Identifier(identifier) => {
 				match iter.next() {
 					Some(Operator(LeftParen)) => {
+						let start = iter.as_slice();
 						let arguments = process_function_parameters(&mut iter);
+						let consumed = &start[..start.len() - iter.as_slice().len()];
 
-						if (identifier.ends_with('!')) {
+						/* calls whose Result gets checked with ?, like 'write(fd, buf)?' */
+						if let Some(Operator(Question)) = iter.clone().next() {
+							let mut expr = vec![Identifier(identifier.clone()), Operator(LeftParen)];
+							expr.extend(consumed.iter().filter(|x| !matches!(x, Newline)).cloned());
+							expr.append(&mut seperate_expression(&mut iter, &Semicolon));
+
+							ast.push(AstType::TryStatement(expr));
+						}
+						else if (identifier.ends_with('!')) {
 							ast.push(AstType::MacroCall(identifier.to_owned(), arguments));
 						}
 						else {
 							ast.push(AstType::FunctionCall(identifier.to_owned(), arguments));
 						}
+
+						/* the arguments can go over multiple lines, like with asm!, so the newlines in them get added after the call */
+						for _ in consumed.iter().filter(|x| matches!(x, Newline)) {
+							line += 1;
+							ast.push(AstType::Newline);
+						}
 					},
 					Some(Operator(Equal)) => {
 						let expr = seperate_expression(&mut iter, &Semicolon);
 
 						ast.push(AstType::VariableAssigment(identifier.to_owned(), expr));
 					},
+					/* method calls, like 'v.print()' */
+					Some(Operator(Dot)) => {
+						let mut expr = vec![Identifier(identifier.clone()), Operator(Dot)];
+						expr.append(&mut seperate_expression(&mut iter, &Semicolon));
+
+						if let Some(Operator(Question)) = expr.last() {
+							ast.push(AstType::TryStatement(expr));
+						}
+						else {
+							ast.push(AstType::MethodCall(expr));
+						}
+					}
 					/* arithmetic assignment operators, like +=, -=, *= and /= */
 					Some(Operator(x)) => {
 						let op = match x {
@@ -430,5 +832,9 @@ pub fn parse(input: Vec<Token>) -> Result<Vec<AstType>, (String, i64)> {
 		}
 	}
 
+	if (!attributes.is_empty()) {
+		return Err(("expected a function after attributes, but the file ended".to_owned(), line));
+	}
+
 	Ok(ast)
 }
\ No newline at end of file
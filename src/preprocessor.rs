@@ -0,0 +1,290 @@
+use std::collections::HashMap;
+
+use crate::lexer::{Token::{self, *}, Operator::*, Keyword};
+use crate::codegen;
+
+/* this module expands user defined macros, which happens on the tokens of a file before it gets parsed */
+/* a macro is defined like this: */
+/*
+	macro swap!(a, b) {
+		let tmp = a
+		a = b
+		b = tmp
+	}
+*/
+/* and every call to it gets replaced with its body, with the paramaters replaced by the tokens of the arguments */
+
+/* macros that call themselves would expand forever, so we give up after this many nested expansions */
+const MAX_EXPANSION_DEPTH: usize = 64;
+
+struct Macro {
+	params: Vec<String>,
+	body: Vec<Token>,
+	line: i64
+}
+
+struct Preprocessor {
+	macros: HashMap<String, Macro>,
+	/* every expansion gets a unique number, which is used to rename the locals of the macro body */
+	expansions: usize,
+	notes: Notes
+}
+
+/* line -> a note about which macros were expanded on it, so that errors in expanded code can point to the definition too */
+pub type Notes = HashMap<i64, String>;
+
+/* splits the arguments of a macro call at the commas that aren't nested inside of parentheses */
+/* expects the iterator to be right after the opening parenthesis, and consumes the closing one */
+fn split_arguments(iter: &mut std::iter::Peekable<std::vec::IntoIter<Token>>, name: &str, line: i64) -> Result<Vec<Vec<Token>>, (String, i64)> {
+	let mut args = Vec::new();
+	let mut arg = Vec::new();
+	let mut depth = 0;
+
+	loop {
+		match iter.next() {
+			Some(Operator(RightParen)) if depth == 0 => break,
+			Some(Operator(Comma)) if depth == 0 => args.push(std::mem::take(&mut arg)),
+			Some(Newline) | None => return Err((format!("expected ')' to close the arguments of the call to {name}"), line)),
+			Some(x) => {
+				match x {
+					Operator(LeftParen) => depth += 1,
+					Operator(RightParen) => depth -= 1,
+					_ => ()
+				}
+
+				arg.push(x);
+			}
+		}
+	}
+
+	if (!arg.is_empty() || !args.is_empty()) {
+		args.push(arg);
+	}
+
+	Ok(args)
+}
+
+/* reads a macro definition, the 'macro' keyword has already been consumed */
+/* the newlines inside of the definition get pushed to 'output' so that the line numbers after it stay correct */
+fn read_definition(iter: &mut std::iter::Peekable<std::vec::IntoIter<Token>>, output: &mut Vec<Token>, line: &mut i64) -> Result<(String, Macro), (String, i64)> {
+	let definition_line = *line;
+
+	let name = match iter.next() {
+		Some(Identifier(x)) if x.ends_with('!') && x.len() > 1 => x,
+		_ => return Err(("expected the name of a macro followed by '!' after macro keyword, like 'macro name!(a, b)'".to_owned(), *line))
+	};
+
+	/* macros that are built into the compiler (see codegen/macros.rs) can't be redefined */
+	/* (cfg! can't either, but that one is already gone by now, see cfg.rs) */
+	if (codegen::is_builtin_macro(&name)) {
+		return Err((format!("cannot define macro {name}, there's already a builtin macro with that name"), *line));
+	}
+
+	if (!matches!(iter.next(), Some(Operator(LeftParen)))) {
+		return Err((format!("expected '(' after the name of macro {name}"), *line));
+	}
+
+	let mut params = Vec::new();
+	loop {
+		match iter.next() {
+			Some(Operator(RightParen)) if params.is_empty() => break,
+			Some(Identifier(x)) => {
+				if (params.contains(&x)) {
+					return Err((format!("paramater '{x}' of macro {name} is defined more than once"), *line));
+				}
+				params.push(x);
+			}
+			_ => return Err((format!("expected a paramater name in the paramater list of macro {name}"), *line))
+		}
+
+		match iter.next() {
+			Some(Operator(Comma)) => (),
+			Some(Operator(RightParen)) => break,
+			_ => return Err((format!("expected ',' or ')' after paramater in the paramater list of macro {name}"), *line))
+		}
+	}
+
+	if (!matches!(iter.next(), Some(Operator(LeftCurly)))) {
+		return Err((format!("expected '{{' after the paramater list of macro {name}"), *line));
+	}
+
+	/* the body is everything up to the matching closing curly brace */
+	/* newlines in it turn into semicolons, so that expanding a macro doesn't change the line numbers of the code it's expanded into */
+	let mut body = Vec::new();
+	let mut scopes = 1;
+	/* the semicolon only gets added once there's something after the newline, so 'x * x' doesn't become 'x * x;' */
+	let mut semicolon = false;
+	loop {
+		let token = match iter.next() {
+			Some(x) => x,
+			None => return Err((format!("expected '}}' to close the body of macro {name}"), definition_line))
+		};
+
+		match token {
+			Operator(LeftCurly) => scopes += 1,
+			Operator(RightCurly) => {
+				scopes -= 1;
+				if (scopes == 0) {
+					break;
+				}
+			},
+			Newline => {
+				*line += 1;
+				output.push(Newline);
+
+				semicolon = !matches!(body.last(), None | Some(Operator(LeftCurly)) | Some(Operator(Semicolon)));
+				continue;
+			}
+			_ => ()
+		}
+
+		if (std::mem::take(&mut semicolon)) {
+			body.push(Operator(Semicolon));
+		}
+		body.push(token);
+	}
+
+	Ok((name, Macro { params, body, line: definition_line }))
+}
+
+/* returns the names of all of the variables that a macro body defines with let */
+fn body_locals(body: &[Token]) -> Vec<String> {
+	let mut locals = Vec::new();
+
+	for (i, v) in body.iter().enumerate() {
+		if (!matches!(v, Keyword(Keyword::Let))) {
+			continue;
+		}
+
+		match body.get(i+1) {
+			Some(Identifier(x)) => locals.push(x.clone()),
+			/* let (a, b) = ... */
+			Some(Operator(LeftParen)) => {
+				for x in body[i+2..].iter() {
+					match x {
+						Identifier(x) => locals.push(x.clone()),
+						Operator(RightParen) => break,
+						_ => ()
+					}
+				}
+			}
+			_ => ()
+		}
+	}
+
+	locals
+}
+
+impl Preprocessor {
+	/* returns the body of a macro with the arguments substituted into it */
+	fn expand(&mut self, name: &str, args: Vec<Vec<Token>>, line: i64) -> Result<Vec<Token>, (String, i64)> {
+		/* unwrap will never fail, the caller checks that the macro exists */
+		let definition = self.macros.get(name).unwrap();
+
+		if (args.len() != definition.params.len()) {
+			return Err((format!("macro {name} accepts {} arguments but {} {} given (macro {name} is defined on line {})", definition.params.len(), args.len(), if (args.len() == 1) {
+				"was"
+			}
+			else {
+				"were"
+			}, definition.line), line));
+		}
+
+		/* hygiene: variables that the macro defines get renamed, so that they can't clash with variables at the call site */
+		/* the new names can't be typed in a source file, so they can never clash with anything */
+		self.expansions += 1;
+		let locals = body_locals(&definition.body);
+
+		let mut output = Vec::new();
+		for i in &definition.body {
+			match i {
+				Identifier(x) if locals.contains(x) => output.push(Identifier(format!("{x}#{}", self.expansions))),
+				Identifier(x) => match definition.params.iter().position(|param| param == x) {
+					Some(index) => output.extend(args[index].iter().cloned()),
+					None => output.push(i.clone())
+				},
+				_ => output.push(i.clone())
+			}
+		}
+
+		Ok(output)
+	}
+
+	/* expands every call to a user defined macro in 'input' */
+	/* the output of an expansion can call other macros, so it gets expanded again until there's nothing left to expand */
+	fn expand_all(&mut self, input: Vec<Token>, line: i64, depth: usize) -> Result<Vec<Token>, (String, i64)> {
+		let mut output = Vec::new();
+		let mut iter = input.into_iter().peekable();
+
+		while let Some(i) = iter.next() {
+			let name = match &i {
+				Identifier(x) if self.macros.contains_key(x) && matches!(iter.peek(), Some(Operator(LeftParen))) => x.clone(),
+				_ => {
+					output.push(i);
+					continue;
+				}
+			};
+
+			if (depth >= MAX_EXPANSION_DEPTH) {
+				return Err((format!("macro {name} was expanded more than {MAX_EXPANSION_DEPTH} times inside of itself, does it call itself? (macro {name} is defined on line {})", self.macros[&name].line), line));
+			}
+
+			iter.next(); /* skip ( */
+			let args = split_arguments(&mut iter, &name, line)?;
+			let expansion = self.expand(&name, args, line)?;
+
+			/* only the outermost expansion gets mentioned, the rest are inside of its definition */
+			if (depth == 0) {
+				let note = format!("macro {name}, which is defined on line {}", self.macros[&name].line);
+				self.notes.entry(line).and_modify(|x| *x = format!("{x} and {note}")).or_insert(note);
+			}
+
+			output.append(&mut self.expand_all(expansion, line, depth+1)?);
+		}
+
+		Ok(output)
+	}
+}
+
+/* removes every macro definition from the tokens of a file and expands every call to them */
+/* also returns a note for every line that had a macro expanded on it, which should be added to errors on that line */
+pub fn preprocess(input: Vec<Token>) -> Result<(Vec<Token>, Notes), (String, i64)> {
+	let mut preprocessor = Preprocessor {
+		macros: HashMap::new(),
+		expansions: 0,
+		notes: HashMap::new()
+	};
+
+	/* first we take out all of the definitions, so that macros can be used before they're defined */
+	let mut tokens = Vec::new();
+	let mut line = 1;
+
+	let mut iter = input.into_iter().peekable();
+	while let Some(i) = iter.next() {
+		match i {
+			Keyword(Keyword::Macro) => {
+				let (name, definition) = read_definition(&mut iter, &mut tokens, &mut line)?;
+
+				if let Some(x) = preprocessor.macros.get(&name) {
+					return Err((format!("macro {name} is already defined on line {}", x.line), definition.line));
+				}
+
+				preprocessor.macros.insert(name, definition);
+			}
+			Newline => {
+				line += 1;
+				tokens.push(i);
+			}
+			_ => tokens.push(i)
+		}
+	}
+
+	/* then we expand them line by line, so that errors know which line the call was on */
+	let mut output = Vec::new();
+
+	for (line, i) in (1..).zip(tokens.split_inclusive(|x| matches!(x, Newline))) {
+		output.append(&mut preprocessor.expand_all(i.to_vec(), line, 0)?);
+	}
+
+	Ok((output, preprocessor.notes))
+}